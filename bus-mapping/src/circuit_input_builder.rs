@@ -33,7 +33,7 @@ pub use execution::{
 };
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
-use log::warn;
+use log::{debug, warn};
 use std::collections::HashMap;
 pub use transaction::{Transaction, TransactionContext};
 
@@ -300,8 +300,13 @@ impl<'a> CircuitInputBuilder {
     }
 }
 
+/// Length in bytes above which a single keccak input is logged as a warning,
+/// since it takes a large share of the keccak circuit rows on its own. Longer
+/// inputs are still returned.
+pub const KECCAK_INPUT_WARN_LEN: usize = 0x10000;
+
 /// Return all the keccak inputs used during the processing of the current
-/// block.
+/// block. Identical inputs are only returned once, in order of first use.
 pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Error> {
     let mut keccak_inputs = Vec::new();
     // Tx Circuit
@@ -315,6 +320,26 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     keccak_inputs.extend_from_slice(&block.sha3_inputs);
     // MPT Circuit
     // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
+
+    let num_inputs = keccak_inputs.len();
+    let keccak_inputs: Vec<_> = keccak_inputs.into_iter().unique().collect();
+    let largest_input_len = keccak_inputs
+        .iter()
+        .map(|input| input.len())
+        .max()
+        .unwrap_or(0);
+    debug!(
+        "keccak inputs: {} unique out of {}, largest is {} bytes",
+        keccak_inputs.len(),
+        num_inputs,
+        largest_input_len
+    );
+    if largest_input_len > KECCAK_INPUT_WARN_LEN {
+        warn!(
+            "keccak input of {} bytes is longer than {} bytes",
+            largest_input_len, KECCAK_INPUT_WARN_LEN
+        );
+    }
     Ok(keccak_inputs)
 }
 
@@ -7,7 +7,7 @@ use crate::{
     operation::{OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Word};
+use eth_types::{evm_unimplemented, Address, ToWord, Word};
 use std::collections::HashMap;
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -70,6 +70,11 @@ pub struct Block {
     pub timestamp: Word,
     /// gas limit
     pub difficulty: Word,
+    /// prevrandao (mix hash) of the block
+    pub prevrandao: Word,
+    /// Whether the block is post-merge, in which case DIFFICULTY returns
+    /// `prevrandao` instead of `difficulty`
+    pub is_merge: bool,
     /// base fee
     pub base_fee: Word,
     /// State root of the previous block
@@ -122,6 +127,8 @@ impl Block {
                 .into(),
             timestamp: eth_block.timestamp,
             difficulty: eth_block.difficulty,
+            prevrandao: eth_block.mix_hash.unwrap_or_default().to_word(),
+            is_merge: eth_block.difficulty.is_zero(),
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
             prev_state_root,
             container: OperationContainer::new(),
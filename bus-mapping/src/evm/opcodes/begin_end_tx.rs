@@ -247,8 +247,8 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         },
     );
 
-    let effective_refund = refund
-        .min((state.tx.gas() - exec_step.gas_left.0) / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+    let gas_used = state.tx.gas() - exec_step.gas_left.0;
+    let effective_refund = refund.min(gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
     let (found, caller_account) = state.sdb.get_account(&call.caller_address);
     if !found {
         return Err(Error::AccountNotFound(call.caller_address));
@@ -264,14 +264,16 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         caller_balance_prev,
     )?;
 
+    // As in geth, the coinbase is paid and the receipt accounts for the gas
+    // used after the refund.
+    let gas_used = gas_used - effective_refund;
     let effective_tip = state.tx.tx.gas_price - state.block.base_fee;
     let (found, coinbase_account) = state.sdb.get_account(&state.block.coinbase);
     if !found {
         return Err(Error::AccountNotFound(state.block.coinbase));
     }
     let coinbase_balance_prev = coinbase_account.balance;
-    let coinbase_balance =
-        coinbase_balance_prev + effective_tip * (state.tx.gas() - exec_step.gas_left.0);
+    let coinbase_balance = coinbase_balance_prev + effective_tip * gas_used;
     state.account_write(
         &mut exec_step,
         state.block.coinbase,
@@ -306,7 +308,7 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         )?;
     }
 
-    state.block_ctx.cumulative_gas_used += state.tx.gas() - exec_step.gas_left.0;
+    state.block_ctx.cumulative_gas_used += gas_used;
     state.tx_receipt_write(
         &mut exec_step,
         state.tx_ctx.id(),
@@ -70,6 +70,8 @@ pub struct BlockConstants {
     pub number: U64,
     /// difficulty
     pub difficulty: Word,
+    /// prevrandao (mix hash) of a post-merge block, `None` before the merge
+    pub mix_hash: Option<Hash>,
     /// gas limit
     pub gas_limit: Word,
     /// base fee
@@ -85,6 +87,13 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             timestamp: block.timestamp,
             number: block.number.ok_or(Error::IncompleteBlock)?,
             difficulty: block.difficulty,
+            // After the merge the difficulty is fixed to zero and DIFFICULTY
+            // (PREVRANDAO) returns the mix hash instead.
+            mix_hash: if block.difficulty.is_zero() {
+                block.mix_hash
+            } else {
+                None
+            },
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
         })
@@ -98,6 +107,7 @@ impl BlockConstants {
         timestamp: Word,
         number: U64,
         difficulty: Word,
+        mix_hash: Option<Hash>,
         gas_limit: Word,
         base_fee: Word,
     ) -> BlockConstants {
@@ -106,6 +116,7 @@ impl BlockConstants {
             timestamp,
             number,
             difficulty,
+            mix_hash,
             gas_limit,
             base_fee,
         }
@@ -86,6 +86,7 @@ type Block struct {
 	Timestamp  *hexutil.Big   `json:"timestamp"`
 	Number     *hexutil.Big   `json:"number"`
 	Difficulty *hexutil.Big   `json:"difficulty"`
+	MixHash    *common.Hash   `json:"mix_hash"`
 	GasLimit   *hexutil.Big   `json:"gas_limit"`
 	BaseFee    *hexutil.Big   `json:"base_fee"`
 }
@@ -195,6 +196,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		BlockNumber: toBigInt(config.Block.Number),
 		Time:        toBigInt(config.Block.Timestamp).Uint64(),
 		Difficulty:  toBigInt(config.Block.Difficulty),
+		Random:      config.Block.MixHash,
 		BaseFee:     toBigInt(config.Block.BaseFee),
 		GasLimit:    blockGasLimit,
 	}
@@ -217,7 +219,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 	executionResults := make([]*ExecutionResult, len(config.Transactions))
 	for i, message := range messages {
 		tracer := logger.NewStructLogger(config.LoggerConfig)
-		evm := vm.NewEVM(blockCtx, core.NewEVMTxContext(&message), stateDB, &chainConfig, vm.Config{Debug: true, Tracer: tracer, NoBaseFee: true})
+		evm := vm.NewEVM(blockCtx, core.NewEVMTxContext(&message), stateDB, &chainConfig, vm.Config{Debug: true, Tracer: tracer, NoBaseFee: true, ExtraEips: []int{3855}})
 
 		result, err := core.ApplyMessage(evm, &message, new(core.GasPool).AddGas(message.GasLimit))
 		if err != nil {
@@ -1,7 +1,7 @@
 //! Mock Block definition and builder related methods.
 
 use crate::{MockTransaction, MOCK_BASEFEE, MOCK_CHAIN_ID, MOCK_DIFFICULTY, MOCK_GASLIMIT};
-use eth_types::{Address, Block, Bytes, Hash, Transaction, Word, H64, U64};
+use eth_types::{Address, Block, Bytes, Hash, ToBigEndian, Transaction, Word, H64, U64};
 use ethers_core::types::{Bloom, OtherFields};
 
 #[derive(Clone, Debug)]
@@ -223,6 +223,15 @@ impl MockBlock {
         self
     }
 
+    /// Turn the MockBlock into a post-merge block whose DIFFICULTY
+    /// (PREVRANDAO) opcode returns `prevrandao`. The difficulty is set to
+    /// zero and the value is stored as the mix hash.
+    pub fn prevrandao(&mut self, prevrandao: Word) -> &mut Self {
+        self.difficulty = Word::zero();
+        self.mix_hash = Hash::from(prevrandao.to_be_bytes());
+        self
+    }
+
     /// Set total_difficulty field for the MockBlock.
     pub fn total_difficulty(&mut self, total_difficulty: Word) -> &mut Self {
         self.total_difficulty = total_difficulty;
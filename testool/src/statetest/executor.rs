@@ -132,6 +132,7 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 timestamp: U256::from(st.env.current_timestamp),
                 number: U64::from(st.env.current_number),
                 difficulty: st.env.current_difficulty,
+                mix_hash: None,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: U256::one(),
             },
@@ -1,8 +1,10 @@
 use crate::{
+    evm_circuit::util::rlc,
     table::BytecodeFieldTag,
     util::{get_push_size, keccak},
 };
-use eth_types::{Field, Word};
+use eth_types::{Field, ToLittleEndian, Word};
+use halo2_proofs::circuit::Value;
 use std::vec;
 
 /// Public data for the bytecode
@@ -13,6 +15,9 @@ pub(crate) struct BytecodeRow<F: Field> {
     pub(crate) index: F,
     pub(crate) is_code: F,
     pub(crate) value: F,
+    /// Running RLC of the bytes up to and including this row, zero on the
+    /// header row. Only set by `unroll_with_rlc`.
+    pub(crate) value_rlc: Option<F>,
 }
 
 /// Unrolled bytecode
@@ -22,6 +27,14 @@ pub struct UnrolledBytecode<F: Field> {
     pub(crate) rows: Vec<BytecodeRow<F>>,
 }
 
+impl<F: Field> UnrolledBytecode<F> {
+    /// Get the RLC of the code hash using the given challenge. Only the code
+    /// hash of the header row is used.
+    pub fn code_hash_rlc(&self, challenge: Value<F>) -> Value<F> {
+        challenge.map(|challenge| rlc::value(&self.rows[0].code_hash.to_le_bytes(), challenge))
+    }
+}
+
 /// Get unrolled bytecode from raw bytes
 pub fn unroll<F: Field>(bytes: Vec<u8>) -> UnrolledBytecode<F> {
     let code_hash = keccak(&bytes[..]);
@@ -31,6 +44,7 @@ pub fn unroll<F: Field>(bytes: Vec<u8>) -> UnrolledBytecode<F> {
         index: F::ZERO,
         is_code: F::ZERO,
         value: F::from(bytes.len() as u64),
+        value_rlc: None,
     }];
     // Run over all the bytes
     let mut push_rindex = 0;
@@ -49,7 +63,24 @@ pub fn unroll<F: Field>(bytes: Vec<u8>) -> UnrolledBytecode<F> {
             index: F::from(index as u64),
             is_code: F::from(is_code as u64),
             value: F::from(*byte as u64),
+            value_rlc: None,
         });
     }
     UnrolledBytecode { bytes, rows }
 }
+
+/// Get unrolled bytecode from raw bytes, with the running RLC of the bytes
+/// accumulated in every row using the given challenge.
+pub fn unroll_with_rlc<F: Field>(bytes: Vec<u8>, challenge: F) -> UnrolledBytecode<F> {
+    let mut unrolled = unroll(bytes);
+    let mut value_rlc = F::ZERO;
+    for row in unrolled.rows.iter_mut() {
+        value_rlc = if row.tag == F::from(BytecodeFieldTag::Header as u64) {
+            F::ZERO
+        } else {
+            value_rlc * challenge + row.value
+        };
+        row.value_rlc = Some(value_rlc);
+    }
+    unrolled
+}
@@ -63,6 +63,12 @@ impl<F: Field> BytecodeCircuitRow<F> {
     }
 }
 
+/// Challenge used for the RLC of the code hash. It has to be the one the
+/// keccak table uses for its output RLC, since both are looked up together.
+fn code_hash_challenge<T: Clone>(challenges: &Challenges<T>) -> T {
+    challenges.evm_word()
+}
+
 #[derive(Clone, Debug)]
 /// Bytecode circuit configuration
 pub struct BytecodeCircuitConfig<F> {
@@ -274,7 +280,7 @@ impl<F: Field> SubCircuitConfig<F> for BytecodeCircuitConfig<F> {
 
             let empty_hash = rlc::expr(
                 &EMPTY_CODE_HASH_LE.map(|v| Expression::Constant(F::from(v as u64))),
-                challenges.evm_word(),
+                code_hash_challenge(&challenges),
             );
 
             cb.require_equal(
@@ -495,6 +501,8 @@ impl<F: Field> BytecodeCircuitConfig<F> {
             last_row_offset
         );
 
+        let code_hash_challenge = code_hash_challenge(challenges);
+
         layouter.assign_region(
             || "assign bytecode",
             |mut region| {
@@ -507,6 +515,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
                         &mut region,
                         bytecode,
                         challenges,
+                        code_hash_challenge,
                         &mut offset,
                         last_row_offset,
                         fail_fast,
@@ -515,7 +524,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
 
                 // Padding
                 for idx in offset..=last_row_offset {
-                    self.set_padding_row(&mut region, challenges, idx, last_row_offset)?;
+                    self.set_padding_row(&mut region, code_hash_challenge, idx, last_row_offset)?;
                 }
 
                 // Overwrite the witness assignment by using the values in the `overwrite`
@@ -548,8 +557,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
                         value_rlc = challenges.keccak_input().map(|_| F::ZERO);
                     }
 
-                    let code_hash = challenges
-                        .evm_word()
+                    let code_hash = code_hash_challenge
                         .map(|challenge| rlc::value(&row.code_hash.to_le_bytes(), challenge));
                     for (name, column, value) in [
                         ("code_hash", self.bytecode_table.code_hash, code_hash),
@@ -568,11 +576,13 @@ impl<F: Field> BytecodeCircuitConfig<F> {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn assign_bytecode(
         &self,
         region: &mut Region<'_, F>,
         bytecode: &UnrolledBytecode<F>,
         challenges: &Challenges<Value<F>>,
+        code_hash_challenge: Value<F>,
         offset: &mut usize,
         last_row_offset: usize,
         fail_fast: bool,
@@ -586,9 +596,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
 
         // Code hash with challenge is calculated only using the first row of the
         // bytecode (header row), the rest of the code_hash in other rows are ignored.
-        let code_hash = challenges
-            .evm_word()
-            .map(|challenge| rlc::value(&bytecode.rows[0].code_hash.to_le_bytes(), challenge));
+        let code_hash = bytecode.code_hash_rlc(code_hash_challenge);
 
         for (idx, row) in bytecode.rows.iter().enumerate() {
             if fail_fast && *offset > last_row_offset {
@@ -642,7 +650,7 @@ impl<F: Field> BytecodeCircuitConfig<F> {
                 push_data_left = next_push_data_left
             }
             if *offset == last_row_offset {
-                self.set_padding_row(region, challenges, *offset, last_row_offset)?;
+                self.set_padding_row(region, code_hash_challenge, *offset, last_row_offset)?;
             }
         }
 
@@ -652,13 +660,12 @@ impl<F: Field> BytecodeCircuitConfig<F> {
     fn set_padding_row(
         &self,
         region: &mut Region<'_, F>,
-        challenges: &Challenges<Value<F>>,
+        code_hash_challenge: Value<F>,
         offset: usize,
         last_row_offset: usize,
     ) -> Result<(), Error> {
-        let empty_hash = challenges
-            .evm_word()
-            .map(|challenge| rlc::value(EMPTY_CODE_HASH_LE.as_ref(), challenge));
+        let empty_hash =
+            code_hash_challenge.map(|challenge| rlc::value(EMPTY_CODE_HASH_LE.as_ref(), challenge));
 
         self.set_row(
             region,
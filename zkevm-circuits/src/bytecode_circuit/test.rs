@@ -6,7 +6,9 @@ use crate::{
 };
 use bus_mapping::evm::OpcodeId;
 use eth_types::{Bytecode, Field, Word};
-use halo2_proofs::{arithmetic::Field as Halo2Field, dev::MockProver, halo2curves::bn256::Fr};
+use halo2_proofs::{
+    arithmetic::Field as Halo2Field, circuit::Value, dev::MockProver, halo2curves::bn256::Fr,
+};
 use log::error;
 
 #[test]
@@ -73,6 +75,7 @@ fn bytecode_unrolling() {
                 index: Fr::from(rows.len() as u64),
                 is_code: Fr::from(true as u64),
                 value: Fr::from(byte as u64),
+                value_rlc: None,
             });
         }
     }
@@ -89,6 +92,7 @@ fn bytecode_unrolling() {
             index: Fr::from(rows.len() as u64),
             is_code: Fr::from(true as u64),
             value: Fr::from(OpcodeId::PUSH1.as_u64() + ((n - 1) as u64)),
+            value_rlc: None,
         });
         for _ in 0..n {
             rows.push(BytecodeRow {
@@ -97,6 +101,7 @@ fn bytecode_unrolling() {
                 index: Fr::from(rows.len() as u64),
                 is_code: Fr::from(false as u64),
                 value: Fr::from(data_byte as u64),
+                value_rlc: None,
             });
         }
     }
@@ -113,6 +118,7 @@ fn bytecode_unrolling() {
             index: Fr::ZERO,
             is_code: Fr::ZERO,
             value: Fr::from(bytecode.to_vec().len() as u64),
+            value_rlc: None,
         },
     );
     // Unroll the bytecode
@@ -129,6 +135,35 @@ fn bytecode_unrolling() {
     test_bytecode_circuit_unrolled::<Fr>(k, vec![unrolled], true);
 }
 
+/// Verify the code hash RLC follows the challenge it is given
+#[test]
+fn bytecode_rlc_with_challenge() {
+    let bytecode = vec![OpcodeId::PUSH1.as_u8(), 0x42, OpcodeId::STOP.as_u8()];
+    let unrolled = unroll::<Fr>(bytecode);
+    let challenge_a = Value::known(Fr::from(0x100u64));
+    let challenge_b = Value::known(Fr::from(0x101u64));
+
+    unrolled
+        .code_hash_rlc(challenge_a)
+        .zip(unrolled.code_hash_rlc(challenge_b))
+        .assert_if_known(|(a, b)| a != b);
+}
+
+#[test]
+fn bytecode_unrolling_with_rlc() {
+    let bytecode = vec![OpcodeId::PUSH1.as_u8(), 0x42, OpcodeId::STOP.as_u8()];
+    let challenge = Fr::from(0x101u64);
+    let unrolled = unroll_with_rlc::<Fr>(bytecode.clone(), challenge);
+
+    assert_eq!(unrolled.rows[0].value_rlc, Some(Fr::ZERO));
+    assert_eq!(unrolled.rows[1].value_rlc, Some(Fr::from(0x60u64)));
+    // The last row holds the RLC of the whole bytecode
+    let bytecode_rlc = bytecode.iter().fold(Fr::ZERO, |acc, byte| {
+        acc * challenge + Fr::from(*byte as u64)
+    });
+    assert_eq!(unrolled.rows.last().unwrap().value_rlc, Some(bytecode_rlc));
+}
+
 /// Tests a fully empty circuit
 #[test]
 fn bytecode_empty() {
@@ -313,6 +348,7 @@ fn bytecode_soundness_bug_1() {
                 index: Fr::from(index),
                 is_code: Fr::ONE,
                 value: Fr::from((i % 10 + 1) as u64),
+                value_rlc: None,
             });
             index += 1;
         }
@@ -95,6 +95,7 @@ mod origin;
 mod pc;
 mod pop;
 mod push;
+mod push0;
 mod return_revert;
 mod returndatacopy;
 mod returndatasize;
@@ -109,6 +110,8 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 use self::sha3::Sha3Gadget;
 use add_sub::AddSubGadget;
@@ -142,6 +145,7 @@ use error_oog_exp::ErrorOOGExpGadget;
 use error_oog_log::ErrorOOGLogGadget;
 use error_oog_memory_copy::ErrorOOGMemoryCopyGadget;
 use error_oog_sload_sstore::ErrorOOGSloadSstoreGadget;
+use error_oog_static_memory::ErrorOOGStaticMemoryGadget;
 use error_return_data_oo_bound::ErrorReturnDataOutOfBoundGadget;
 use error_stack::ErrorStackGadget;
 use error_write_protection::ErrorWriteProtectionGadget;
@@ -165,6 +169,7 @@ use origin::OriginGadget;
 use pc::PcGadget;
 use pop::PopGadget;
 use push::PushGadget;
+use push0::Push0Gadget;
 use return_revert::ReturnRevertGadget;
 use returndatacopy::ReturnDataCopyGadget;
 use returndatasize::ReturnDataSizeGadget;
@@ -261,6 +266,7 @@ pub struct ExecutionConfig<F> {
     pc_gadget: Box<PcGadget<F>>,
     pop_gadget: Box<PopGadget<F>>,
     push_gadget: Box<PushGadget<F>>,
+    push0_gadget: Box<Push0Gadget<F>>,
     return_revert_gadget: Box<ReturnRevertGadget<F>>,
     sar_gadget: Box<SarGadget<F>>,
     sdiv_smod_gadget: Box<SignedDivModGadget<F>>,
@@ -288,8 +294,7 @@ pub struct ExecutionConfig<F> {
     error_oog_exp: Box<ErrorOOGExpGadget<F>>,
     error_oog_memory_copy: Box<ErrorOOGMemoryCopyGadget<F>>,
     error_oog_sload_sstore: Box<ErrorOOGSloadSstoreGadget<F>>,
-    error_oog_static_memory_gadget:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasStaticMemoryExpansion }>>,
+    error_oog_static_memory_gadget: Box<ErrorOOGStaticMemoryGadget<F>>,
     error_stack: Box<ErrorStackGadget<F>>,
     error_write_protection: Box<ErrorWriteProtectionGadget<F>>,
     error_oog_dynamic_memory_gadget:
@@ -524,6 +529,7 @@ impl<F: Field> ExecutionConfig<F> {
             pc_gadget: configure_gadget!(),
             pop_gadget: configure_gadget!(),
             push_gadget: configure_gadget!(),
+            push0_gadget: configure_gadget!(),
             return_revert_gadget: configure_gadget!(),
             sdiv_smod_gadget: configure_gadget!(),
             selfbalance_gadget: configure_gadget!(),
@@ -1208,6 +1214,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::PC => assign_exec_step!(self.pc_gadget),
             ExecutionState::POP => assign_exec_step!(self.pop_gadget),
             ExecutionState::PUSH => assign_exec_step!(self.push_gadget),
+            ExecutionState::PUSH0 => assign_exec_step!(self.push0_gadget),
             ExecutionState::RETURN_REVERT => assign_exec_step!(self.return_revert_gadget),
             ExecutionState::RETURNDATASIZE => assign_exec_step!(self.returndatasize_gadget),
             ExecutionState::RETURNDATACOPY => assign_exec_step!(self.returndatacopy_gadget),
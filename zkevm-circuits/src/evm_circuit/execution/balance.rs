@@ -67,6 +67,8 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
         });
 
         cb.stack_push(balance.expr());
+        // The balance is only read when the account exists
+        cb.allowed_rw_counter_deltas(&[7, 8]);
 
         let gas_cost = select::expr(
             is_warm.expr(),
@@ -75,7 +77,7 @@ impl<F: Field> ExecutionGadget<F> for BalanceGadget<F> {
         );
 
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(7.expr() + exists.expr()),
+            rw_counter: cb.rw_counter_delta(),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(0.expr()),
             gas_left: Delta(-gas_cost),
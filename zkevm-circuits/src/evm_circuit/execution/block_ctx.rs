@@ -188,9 +188,15 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU256Gadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{bytecode, Word};
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
 
     fn test_ok(bytecode: bytecode::Bytecode) {
         CircuitTestBuilder::new_from_test_ctx(
@@ -230,4 +236,36 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    #[test]
+    fn blockcxt_prevrandao_gadget_test() {
+        let prevrandao = Word::from_big_endian(&[0xab; 32]) - Word::from(0x1234_5678u64);
+        let bytecode = bytecode! {
+            DIFFICULTY
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _txs| block.prevrandao(prevrandao),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                assert!(block.context.is_merge);
+                let step = block.txs[0]
+                    .steps
+                    .iter()
+                    .find(|step| step.execution_state() == ExecutionState::SSTORE)
+                    .unwrap();
+                let (value, _, _, _) = block.get_rws(step, 7).storage_value_aux();
+                assert_eq!(value, prevrandao);
+                assert_ne!(value >> 128, Word::zero());
+            }))
+            .run();
+    }
 }
@@ -40,7 +40,7 @@ impl<F: Field> ExecutionGadget<F> for DupGadget<F> {
 
         // State transition
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(2.expr()),
+            rw_counter: cb.rw_counter_delta(),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
             gas_left: Delta(-OpcodeId::DUP1.constant_gas_cost().expr()),
@@ -82,7 +82,9 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             None,
         );
 
-        // Add gas_used * effective_tip to coinbase's balance
+        // Add gas_used * effective_tip to coinbase's balance, where gas_used is
+        // taken after the refund as in geth
+        let gas_used = gas_used - effective_refund.min();
         let coinbase = cb.query_cell();
         let base_fee = cb.query_word_rlc();
         for (tag, value) in [
@@ -251,6 +253,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             [effective_tip, block.context.base_fee],
             tx.gas_price,
         )?;
+        let gas_used = gas_used - effective_refund;
         self.mul_effective_tip_by_gas_used.assign(
             region,
             offset,
@@ -308,11 +311,22 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
     use bus_mapping::circuit_input_builder::CircuitsParams;
-    use eth_types::{self, bytecode};
+    use eth_types::{
+        self, bytecode,
+        evm_types::{OpcodeId, MAX_REFUND_QUOTIENT_OF_GAS_USED},
+        Bytecode, Word,
+    };
 
-    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use mock::{
+        eth,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_ACCOUNTS,
+    };
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
@@ -323,22 +337,70 @@ mod test {
             .run();
     }
 
+    /// Clear `num_slots` storage slots so that the tx accumulates a refund of
+    /// `num_slots * SSTORE_CLEARS_SCHEDULE`, then check that the EndTx step
+    /// pays back the (capped) refund to the caller and the tip to the
+    /// coinbase.
+    fn test_refund(num_slots: u64, is_capped: bool) {
+        let mut code = Bytecode::default();
+        for slot in 0..num_slots {
+            code.push(1, Word::zero())
+                .push(1, Word::from(slot))
+                .write_op(OpcodeId::SSTORE);
+        }
+        code.write_op(OpcodeId::STOP);
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code)
+                    .storage((0..num_slots).map(|slot| (Word::from(slot), Word::one())));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(move |block| {
+                let tx = &block.txs[0];
+                let step = tx.steps.last().unwrap();
+                assert_eq!(step.execution_state(), ExecutionState::EndTx);
+
+                let gas_used = tx.gas - step.gas_left.0;
+                let (refund, _) = block.get_rws(step, 2).tx_refund_value_pair();
+                let max_refund = gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64;
+                assert!(refund > 0);
+                assert_eq!(refund > max_refund, is_capped);
+                let effective_refund = refund.min(max_refund);
+
+                let (caller_balance, caller_balance_prev) =
+                    block.get_rws(step, 3).account_value_pair();
+                assert_eq!(
+                    caller_balance,
+                    caller_balance_prev + tx.gas_price * (step.gas_left.0 + effective_refund)
+                );
+                // geth pays the tip and reports gas used after the refund
+                let gas_used = gas_used - effective_refund;
+                let (coinbase_balance, coinbase_balance_prev) =
+                    block.get_rws(step, 4).account_value_pair();
+                assert_eq!(
+                    coinbase_balance,
+                    coinbase_balance_prev + (tx.gas_price - block.context.base_fee) * gas_used
+                );
+                // The first tx writes its CumulativeGasUsed receipt after
+                // PostStateOrStatus and LogLength
+                assert_eq!(block.get_rws(step, 7).receipt_value(), gas_used);
+            }))
+            .run();
+    }
+
     #[test]
     fn end_tx_gadget_simple() {
-        // TODO: Enable this with respective code when SSTORE is implemented.
-        // Tx with non-capped refund
-        // test_ok(vec![mock_tx(
-        //     address!("0x00000000000000000000000000000000000000fe"),
-        //     Some(27000),
-        //     None,
-        // )]);
-        // Tx with capped refund
-        // test_ok(vec![mock_tx(
-        //     address!("0x00000000000000000000000000000000000000fe"),
-        //     Some(65000),
-        //     None,
-        // )]);
-
         // Multiple txs
         test_ok(
             // Get the execution steps from the external tracer
@@ -364,4 +426,16 @@ mod test {
             .unwrap(),
         );
     }
+
+    #[test]
+    fn end_tx_gadget_refund_not_capped() {
+        // A single cleared slot refunds less than gas_used / 5
+        test_refund(1, false);
+    }
+
+    #[test]
+    fn end_tx_gadget_refund_capped() {
+        // Two cleared slots refund more than gas_used / 5
+        test_refund(2, true);
+    }
 }
@@ -8,8 +8,8 @@ use crate::{
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             from_bytes,
             math_gadget::{IsZeroGadget, LtGadget},
-            memory_gadget::{MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget},
-            select, CachedRegion, Cell, Word,
+            memory_gadget::{MemoryCopierGasGadget, MemoryExpansionGadget, U64OverflowGadget},
+            or, select, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
@@ -35,8 +35,8 @@ pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     external_address: Word<F>,
     /// Source offset
     src_offset: Word<F>,
-    /// Destination offset and size to copy
-    dst_memory_addr: MemoryAddressGadget<F>,
+    /// Destination offset and size to copy, which may overflow
+    dst_memory_addr: U64OverflowGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
@@ -62,7 +62,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             ],
         );
 
-        let dst_offset = cb.query_cell_phase2();
+        let dst_offset = cb.query_word_rlc();
         let src_offset = cb.query_word_rlc();
         let copy_size = cb.query_word_rlc();
         let external_address = cb.query_word_rlc();
@@ -90,8 +90,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         cb.stack_pop(src_offset.expr());
         cb.stack_pop(copy_size.expr());
 
-        let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_offset, copy_size);
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [dst_memory_addr.address()]);
+        let dst_memory_addr = U64OverflowGadget::construct(cb, dst_offset, copy_size);
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [dst_memory_addr.end_offset()]);
         let memory_copier_gas = MemoryCopierGasGadget::construct(
             cb,
             dst_memory_addr.length(),
@@ -118,8 +118,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         );
 
         cb.require_equal(
-            "Gas left is less than gas cost",
-            insufficient_gas.expr(),
+            "Memory address overflows or gas left is less than gas cost",
+            or::expr([dst_memory_addr.overflow(), insufficient_gas.expr()]),
             1.expr(),
         );
 
@@ -188,7 +188,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             .assign(region, offset, Some(external_address.to_le_bytes()))?;
         self.src_offset
             .assign(region, offset, Some(src_offset.to_le_bytes()))?;
-        let memory_addr = self
+        let (within_range, memory_addr) = self
             .dst_memory_addr
             .assign(region, offset, dst_offset, copy_size)?;
         let (_, memory_expansion_cost) =
@@ -197,7 +197,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         let memory_copier_gas = self.memory_copier_gas.assign(
             region,
             offset,
-            copy_size.as_u64(),
+            if within_range { copy_size.low_u64() } else { 0 },
             memory_expansion_cost,
         )?;
         let constant_gas_cost = if is_extcodecopy {
@@ -285,6 +285,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_memory_copy_with_overflow() {
+        for (opcode, (dst_offset, copy_size)) in [OpcodeId::CALLDATACOPY, OpcodeId::CODECOPY]
+            .iter()
+            .cartesian_product([
+                // Offset doesn't fit in u64
+                (U256::one() << 70, U256::from(0x20)),
+                // Size doesn't fit in u64
+                (U256::from(0x20), U256::one() << 64),
+                // Offset + size doesn't fit in u64
+                (U256::from(u64::MAX - 0x10), U256::from(0x20)),
+                // Offset + size exceeds the max memory size
+                (U256::from(0x1FFFFFFFE0u64), U256::one()),
+            ])
+        {
+            let testing_data = TestingData::new_for_overflow(*opcode, dst_offset, copy_size);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
     struct TestingData {
         bytecode: Bytecode,
         gas_cost: u64,
@@ -308,6 +330,20 @@ mod tests {
             Self { bytecode, gas_cost }
         }
 
+        pub fn new_for_overflow(opcode: OpcodeId, dst_offset: U256, copy_size: U256) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(rand_word())
+                PUSH32(dst_offset)
+                .write_op(opcode)
+            };
+
+            // Leave plenty of gas for the opcode, it halts due to the overflow.
+            let gas_cost = OpcodeId::PUSH32.constant_gas_cost().0 * 3 + 10_000;
+
+            Self { bytecode, gas_cost }
+        }
+
         pub fn new_for_extcodecopy(is_warm: bool, dst_offset: u64, copy_size: u64) -> Self {
             let external_address = MOCK_ACCOUNTS[4];
 
@@ -358,7 +394,20 @@ mod tests {
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                // The error halts the root call and consumes all the gas left
+                let steps = &block.txs[0].steps;
+                let index = steps
+                    .iter()
+                    .position(|step| {
+                        step.execution_state() == ExecutionState::ErrorOutOfGasMemoryCopy
+                    })
+                    .unwrap();
+                assert_eq!(steps[index + 1].execution_state(), ExecutionState::EndTx);
+                assert_eq!(steps[index + 1].gas_left.0, 0);
+            }))
+            .run();
     }
 
     fn test_internal(testing_data: &TestingData) {
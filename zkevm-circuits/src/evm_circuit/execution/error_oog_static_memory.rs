@@ -4,35 +4,30 @@ use crate::{
         param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
         step::ExecutionState,
         util::{
-            constraint_builder::EVMConstraintBuilder,
-            math_gadget::{IsEqualGadget, IsZeroGadget, RangeCheckGadget},
-            memory_gadget::{address_high, address_low, MemoryExpansionGadget},
-            CachedRegion, Cell, Word,
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, LtGadget},
+            memory_gadget::{MemoryExpansionGadget, U64OverflowGadget},
+            or, select, CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     util::Expr,
 };
-use eth_types::{evm_types::OpcodeId, Field, ToLittleEndian};
-use halo2_proofs::plonk::Error;
+use eth_types::{evm_types::OpcodeId, Field, U256};
+use halo2_proofs::{circuit::Value, plonk::Error};
 
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::MLOAD`], [`OpcodeId::MSTORE`] and [`OpcodeId::MSTORE8`].
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     opcode: Cell<F>,
-    address: Word<F>,
-    address_in_range: IsZeroGadget<F>,
-    // Allow memory size to expand to 5 bytes, because memory address could be
-    // at most 2^40 - 1, after constant division by 32, the memory word size
-    // then could be at most 2^35 - 1.
-    // So generic N_BYTES_MEMORY_WORD_SIZE for MemoryExpansionGadget needs to
-    // be larger by 1 than normal usage (to be 5), to be able to contain
-    // number up to 2^35 - 1.
-    memory_expansion: MemoryExpansionGadget<F, 1, { N_BYTES_MEMORY_WORD_SIZE + 1 }>,
-    // Even memory size at most could be 2^35 - 1, the qudratic part of memory
-    // expansion gas cost could be at most 2^61 - 2^27, due to the constant
-    // division by 512, which still fits in 8 bytes.
-    insufficient_gas: RangeCheckGadget<F, N_BYTES_GAS>,
+    /// Accessed memory range, which may overflow
+    memory_addr: U64OverflowGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_mstore8: IsEqualGadget<F>,
+    common_error_gadget: CommonErrorGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
@@ -40,49 +35,55 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 
     const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasStaticMemoryExpansion;
 
-    // Support other OOG due to pure memory including CREATE, RETURN and REVERT
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
+        cb.require_in_set(
+            "ErrorOutOfGasStaticMemoryExpansion opcode must be MLOAD, MSTORE or MSTORE8",
+            opcode.expr(),
+            vec![
+                OpcodeId::MLOAD.expr(),
+                OpcodeId::MSTORE.expr(),
+                OpcodeId::MSTORE8.expr(),
+            ],
+        );
 
-        // Query address by a full word
-        let address = cb.query_word_rlc();
+        let memory_offset = cb.query_word_rlc();
+        let memory_length = cb.query_word_rlc();
+        cb.stack_pop(memory_offset.expr());
 
-        // Check if this is an MSTORE8
+        // MSTORE8 accesses a single byte, MLOAD and MSTORE a full word.
         let is_mstore8 = IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::MSTORE8.expr());
-        let is_not_mstore8 = 1.expr() - is_mstore8.expr();
+        cb.require_equal(
+            "Memory length is 1 for MSTORE8 and 32 for MLOAD and MSTORE",
+            memory_length.expr(),
+            select::expr(is_mstore8.expr(), 1.expr(), 32.expr()),
+        );
 
-        // Get the next memory size and the gas cost for this memory access
-        let memory_expansion = MemoryExpansionGadget::construct(
+        let memory_addr = U64OverflowGadget::construct(cb, memory_offset, memory_length);
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_addr.end_offset()]);
+
+        // Constant gas cost is same for MLOAD, MSTORE and MSTORE8.
+        let insufficient_gas = LtGadget::construct(
             cb,
-            [address_low::expr(&address) + 1.expr() + (is_not_mstore8 * 31.expr())],
+            cb.curr.state.gas_left.expr(),
+            OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost(),
+        );
+
+        cb.require_equal(
+            "Memory address overflows or gas left is less than gas cost",
+            or::expr([memory_addr.overflow(), insufficient_gas.expr()]),
+            1.expr(),
         );
 
-        // Check if the memory address is too large
-        let address_in_range = IsZeroGadget::construct(cb, address_high::expr(&address));
-        // Check if the amount of gas available is less than the amount of gas
-        // required
-        let insufficient_gas = cb.condition(address_in_range.expr(), |cb| {
-            RangeCheckGadget::construct(
-                cb,
-                OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost()
-                    - cb.curr.state.gas_left.expr(),
-            )
-        });
-
-        // Pop the address from the stack
-        // We still have to do this to verify the correctness of `address`
-        cb.stack_pop(address.expr());
-
-        // TODO: Use ContextSwitchGadget to switch call context to caller's and
-        // consume all gas_left.
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 3.expr());
 
         Self {
             opcode,
-            address,
-            address_in_range,
+            memory_addr,
             memory_expansion,
             insufficient_gas,
             is_mstore8,
+            common_error_gadget,
         }
     }
 
@@ -92,47 +93,214 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
         offset: usize,
         block: &Block<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         let opcode = step.opcode().unwrap();
 
-        // Inputs/Outputs
-        let address = block.get_rws(step, 0).stack_value();
-        self.address
-            .assign(region, offset, Some(address.to_le_bytes()))?;
+        log::debug!(
+            "ErrorOutOfGasStaticMemoryExpansion: opcode = {}, gas_left = {}, gas_cost = {}",
+            opcode,
+            step.gas_left,
+            step.gas_cost,
+        );
+
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
 
-        // Check if this is an MSTORE8
         let is_mstore8 = self.is_mstore8.assign(
             region,
             offset,
             F::from(opcode.as_u64()),
             F::from(OpcodeId::MSTORE8.as_u64()),
         )?;
+        let memory_length = if is_mstore8 == F::ONE { 1 } else { 32 };
 
-        // Address in range check
-        self.address_in_range.assign(
+        let memory_offset = block.get_rws(step, 0).stack_value();
+        let (_, memory_addr) =
+            self.memory_addr
+                .assign(region, offset, memory_offset, U256::from(memory_length))?;
+        let (_, memory_expansion_cost) =
+            self.memory_expansion
+                .assign(region, offset, step.memory_word_size(), [memory_addr])?;
+        self.insufficient_gas.assign_value(
             region,
             offset,
-            address_high::value::<F>(address.to_le_bytes()),
+            Value::known(F::from(step.gas_left.0)),
+            Value::known(F::from(
+                OpcodeId::MLOAD.constant_gas_cost().0 + memory_expansion_cost,
+            )),
         )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 3)?;
 
-        // Memory expansion
-        self.memory_expansion.assign(
-            region,
-            offset,
-            step.memory_word_size(),
-            [
-                address_low::value(address.to_le_bytes())
-                    + if is_mstore8 == F::ONE { 1 } else { 32 },
-            ],
-        )?;
+        Ok(())
+    }
+}
 
-        // Gas insufficient check
-        // Get `gas_available` variable here once it's available
-        self.insufficient_gas
-            .assign(region, offset, F::from(step.gas_cost.0 - step.gas_left.0))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        evm_circuit::test::{rand_bytes, rand_word},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{gas_utils::memory_expansion_gas_cost, GasCost},
+        Bytecode, ToWord,
+    };
+    use itertools::Itertools;
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
 
-        Ok(())
+    const TESTING_OPCODES: &[OpcodeId] = &[OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8];
+
+    #[test]
+    fn test_oog_static_memory() {
+        for (opcode, memory_offset) in TESTING_OPCODES.iter().cartesian_product([0x20, 0x2000]) {
+            let testing_data = TestingData::new(*opcode, memory_offset);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    #[test]
+    fn test_oog_static_memory_with_overflow() {
+        for (opcode, memory_offset) in TESTING_OPCODES.iter().cartesian_product([
+            // The end of the accessed range doesn't fit in u64
+            U256::from(u64::MAX - 15),
+            // Offset doesn't fit in u64
+            U256::one() << 70,
+            // The end of the accessed range exceeds the max memory size
+            U256::from(0x1FFFFFFFE0u64),
+        ]) {
+            let testing_data = TestingData::new_for_overflow(*opcode, memory_offset);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    struct TestingData {
+        bytecode: Bytecode,
+        gas_cost: u64,
+    }
+
+    impl TestingData {
+        pub fn new(opcode: OpcodeId, memory_offset: u64) -> Self {
+            let memory_length = if opcode == OpcodeId::MSTORE8 { 1 } else { 32 };
+            let memory_word_size = (memory_offset + memory_length + 31) / 32;
+
+            let gas_cost = Self::push_gas_cost(opcode)
+                + opcode.constant_gas_cost().0
+                + memory_expansion_gas_cost(0, memory_word_size);
+
+            Self {
+                bytecode: Self::bytecode(opcode, memory_offset.into()),
+                gas_cost,
+            }
+        }
+
+        pub fn new_for_overflow(opcode: OpcodeId, memory_offset: U256) -> Self {
+            // Leave plenty of gas for the opcode, it halts due to the overflow.
+            let gas_cost = Self::push_gas_cost(opcode) + 10_000;
+
+            Self {
+                bytecode: Self::bytecode(opcode, memory_offset),
+                gas_cost,
+            }
+        }
+
+        fn bytecode(opcode: OpcodeId, memory_offset: U256) -> Bytecode {
+            let mut bytecode = Bytecode::default();
+            if opcode != OpcodeId::MLOAD {
+                bytecode.push(32, rand_word());
+            }
+            bytecode.push(32, memory_offset);
+            bytecode.write_op(opcode);
+            bytecode
+        }
+
+        fn push_gas_cost(opcode: OpcodeId) -> u64 {
+            let num_pushes = if opcode == OpcodeId::MLOAD { 1 } else { 2 };
+            OpcodeId::PUSH32.constant_gas_cost().0 * num_pushes
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX.0 + testing_data.gas_cost - 1).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                // The error halts the root call and consumes all the gas left
+                let steps = &block.txs[0].steps;
+                let index = steps
+                    .iter()
+                    .position(|step| {
+                        step.execution_state() == ExecutionState::ErrorOutOfGasStaticMemoryExpansion
+                    })
+                    .unwrap();
+                assert_eq!(steps[index + 1].execution_state(), ExecutionState::EndTx);
+                assert_eq!(steps[index + 1].gas_left.0, 0);
+            }))
+            .run();
+    }
+
+    fn test_internal(testing_data: &TestingData) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // code B gets called by code A, so the call is an internal call.
+        let code_b = testing_data.bytecode.clone();
+        let gas_cost_b = testing_data.gas_cost;
+
+        // Code A calls code B.
+        let code_a = bytecode! {
+            // populate memory in A's context.
+            PUSH8(U256::from_big_endian(&rand_bytes(8)))
+            PUSH1(0x00) // offset
+            MSTORE
+            // call ADDR_B.
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH32(0x00) // argsLength
+            PUSH32(0x20) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            // Decrease expected gas cost (by 1) to trigger out of gas error.
+            PUSH32(gas_cost_b - 1) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(code_b);
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 }
@@ -0,0 +1,104 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
+            CachedRegion,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::Field;
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Push0Gadget<F> {
+    same_context: SameContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Push0Gadget<F> {
+    const NAME: &'static str = "PUSH0";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PUSH0;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Push the constant zero on the stack
+        cb.stack_push(0.expr());
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta((-1).expr()),
+            gas_left: Delta(-OpcodeId::PUSH0.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self { same_context }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        _: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, Bytecode, Word};
+    use mock::TestContext;
+
+    fn test_ok(bytecode: Bytecode) {
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .block_modifier(Box::new(|block| {
+            // Every PUSH0 step writes a zero to the stack
+            let steps = block.txs[0]
+                .steps
+                .iter()
+                .filter(|step| step.execution_state() == ExecutionState::PUSH0)
+                .collect::<Vec<_>>();
+            assert!(!steps.is_empty());
+            for step in steps {
+                assert_eq!(block.get_rws(step, 0).stack_value(), Word::zero());
+            }
+        }))
+        .run();
+    }
+
+    #[test]
+    fn push0_gadget_simple() {
+        test_ok(bytecode! {
+            PUSH0
+            STOP
+        });
+    }
+
+    #[test]
+    fn push0_gadget_consumed() {
+        // The pushed zeros are read back by ADD and MSTORE
+        test_ok(bytecode! {
+            PUSH0
+            PUSH0
+            ADD
+            PUSH0
+            MSTORE
+            STOP
+        });
+    }
+}
@@ -42,7 +42,7 @@ impl<F: Field> ExecutionGadget<F> for SelfbalanceGadget<F> {
 
         let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(3.expr()),
+            rw_counter: cb.rw_counter_delta(),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
             gas_left: Delta(-OpcodeId::SELFBALANCE.constant_gas_cost().expr()),
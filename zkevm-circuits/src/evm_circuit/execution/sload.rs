@@ -1,3 +1,5 @@
+#[cfg(feature = "debug-table")]
+use crate::witness::StorageAccess;
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
@@ -14,7 +16,7 @@ use crate::{
     table::CallContextFieldTag,
     util::Expr,
 };
-use eth_types::{Field, ToScalar};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -69,7 +71,7 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
 
         let gas_cost = SloadGasGadget::construct(cb, is_warm.expr()).expr();
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(8.expr()),
+            rw_counter: cb.rw_counter_delta(),
             program_counter: Delta(1.expr()),
             reversible_write_counter: Delta(1.expr()),
             gas_left: Delta(-gas_cost),
@@ -132,6 +134,35 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
         self.is_warm
             .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
 
+        #[cfg(feature = "debug-table")]
+        block.storage_accesses.record(
+            step.rwc.0,
+            StorageAccess {
+                tx_id: tx.id,
+                address: call.address,
+                key,
+                value,
+                committed_value: committed_value,
+                is_warm,
+            },
+        );
+
+        // The gas charged in the trace must agree with the access list witness
+        let gas_cost = if is_warm {
+            GasCost::WARM_ACCESS
+        } else {
+            GasCost::COLD_SLOAD
+        };
+        if step.gas_cost != gas_cost {
+            log::error!(
+                "SLOAD traced gas cost {} doesn't match is_warm = {} (expected {})",
+                step.gas_cost.0,
+                is_warm,
+                gas_cost.0
+            );
+            return Err(Error::Synthesis);
+        }
+
         Ok(())
     }
 }
@@ -139,11 +170,22 @@ impl<F: Field> ExecutionGadget<F> for SloadGadget<F> {
 #[cfg(test)]
 mod test {
 
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, Word};
+    use super::SloadGadget;
+    use crate::{
+        evm_circuit::{
+            execution::test_util::ExecutionGadgetTester,
+            step::{ExecutionState, HasExecutionState},
+            test::rand_word,
+        },
+        test_util::CircuitTestBuilder,
+        witness::{block_convert, Block, ExecStep, Rw},
+    };
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, evm_types::GasCost, geth_types::GethData, Address, ToWord, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
-    fn test_ok(key: Word, value: Word) {
+    fn test_ctxs(key: Word, value: Word) -> Vec<TestContext<2, 1>> {
         // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
         // Besides, in bytecode we use two SLOADs,
         // the first SLOAD is used to test cold,  and the second is used to test warm
@@ -163,24 +205,31 @@ mod test {
             PUSH32(0)
             REVERT
         };
-        for bytecode in [bytecode_success, bytecode_failure] {
-            let ctx = TestContext::<2, 1>::new(
-                None,
-                |accs| {
-                    accs[0]
-                        .address(MOCK_ACCOUNTS[0])
-                        .balance(Word::from(10u64.pow(19)))
-                        .code(bytecode)
-                        .storage(vec![(key, value)].into_iter());
-                    accs[1]
-                        .address(MOCK_ACCOUNTS[1])
-                        .balance(Word::from(10u64.pow(19)));
-                },
-                tx_from_1_to_0,
-                |block, _txs| block,
-            )
-            .unwrap();
+        [bytecode_success, bytecode_failure]
+            .into_iter()
+            .map(|bytecode| {
+                TestContext::<2, 1>::new(
+                    None,
+                    |accs| {
+                        accs[0]
+                            .address(MOCK_ACCOUNTS[0])
+                            .balance(Word::from(10u64.pow(19)))
+                            .code(bytecode)
+                            .storage(vec![(key, value)].into_iter());
+                        accs[1]
+                            .address(MOCK_ACCOUNTS[1])
+                            .balance(Word::from(10u64.pow(19)));
+                    },
+                    tx_from_1_to_0,
+                    |block, _txs| block,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
 
+    fn test_ok(key: Word, value: Word) {
+        for ctx in test_ctxs(key, value) {
             CircuitTestBuilder::new_from_test_ctx(ctx).run();
         }
     }
@@ -198,4 +247,186 @@ mod test {
         let value = rand_word();
         test_ok(key, value);
     }
+
+    /// Two SLOADs of the same key, the first cold and the second warm.
+    fn sload_twice_ctx(key: Word, value: Word) -> TestContext<2, 1> {
+        let bytecode = bytecode! {
+            PUSH32(key)
+            SLOAD
+            PUSH32(key)
+            SLOAD
+            STOP
+        };
+        TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)))
+                    .code(bytecode)
+                    .storage(vec![(key, value)].into_iter());
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+            },
+            tx_from_1_to_0,
+            |block, _txs| block,
+        )
+        .unwrap()
+    }
+
+    fn nth_sload(block: &mut Block<Fr>, n: usize) -> &mut ExecStep {
+        block.txs[0]
+            .steps
+            .iter_mut()
+            .filter(|step| step.execution_state() == ExecutionState::SLOAD)
+            .nth(n)
+            .unwrap()
+    }
+
+    #[test]
+    #[should_panic(expected = "Synthesis")]
+    fn sload_gadget_warm_with_cold_gas() {
+        let ctx = sload_twice_ctx(0x030201.into(), 0x060504.into());
+
+        // Charge the cold cost on the second (warm) SLOAD. The assignment
+        // rejects it, so MockProver::run fails with Error::Synthesis.
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                nth_sload(block, 1).gas_cost = GasCost::COLD_SLOAD;
+            }))
+            .run();
+    }
+
+    fn sload_twice_block(key: Word, value: Word) -> Block<Fr> {
+        let block: GethData = sload_twice_ctx(key, value).into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        block_convert(&builder.block, &builder.code_db).unwrap()
+    }
+
+    #[test]
+    fn sload_gadget_single_step() {
+        let block = sload_twice_block(0x030201.into(), 0x060504.into());
+        for n in 0..2 {
+            let result = ExecutionGadgetTester::<Fr, SloadGadget<Fr>>::new(block.clone(), n).run();
+            assert_eq!(result, Ok(()));
+        }
+    }
+
+    #[test]
+    fn sload_gadget_wrong_committed_value() {
+        let block = sload_twice_block(0x030201.into(), 0x060504.into());
+
+        // The rw table disagrees with the committed value the SLOAD reads
+        let result = ExecutionGadgetTester::<Fr, SloadGadget<Fr>>::new(block, 0)
+            .rw_table_modifier(|rws| {
+                for rw in rws.iter_mut() {
+                    if let Rw::AccountStorage {
+                        committed_value, ..
+                    } = rw
+                    {
+                        *committed_value = 0x070809.into();
+                    }
+                }
+            })
+            .run();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sload_gadget_wrong_warm_flag() {
+        let block = sload_twice_block(0x030201.into(), 0x060504.into());
+
+        // The rw table marks the slot as warm before the first (cold) SLOAD
+        let result = ExecutionGadgetTester::<Fr, SloadGadget<Fr>>::new(block, 0)
+            .rw_table_modifier(|rws| {
+                for rw in rws.iter_mut() {
+                    if let Rw::TxAccessListAccountStorage { is_warm_prev, .. } = rw {
+                        *is_warm_prev = true;
+                    }
+                }
+            })
+            .run();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sload_gadget_warm_across_delegatecall() {
+        let key = Word::from(0x030201u64);
+        let callee_address = Address::repeat_byte(0xff);
+        // The caller warms the slot, then the callee reads it again in the
+        // caller's storage context.
+        let caller_code = bytecode! {
+            PUSH32(key)
+            SLOAD
+            POP
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH32(callee_address.to_word())
+            PUSH32(Word::from(50000u64))
+            DELEGATECALL
+            STOP
+        };
+        let callee_code = bytecode! {
+            PUSH32(key)
+            SLOAD
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)))
+                    .code(caller_code)
+                    .storage(vec![(key, Word::from(0x060504u64))].into_iter());
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+                accs[2].address(callee_address).code(callee_code);
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _txs| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let parent = nth_sload(block, 0).clone();
+                let child = nth_sload(block, 1).clone();
+                assert_ne!(parent.call_index, child.call_index);
+                assert_eq!(parent.gas_cost, GasCost::COLD_SLOAD);
+                assert_eq!(child.gas_cost, GasCost::WARM_ACCESS);
+            }))
+            .run();
+    }
+
+    #[cfg(feature = "debug-table")]
+    #[test]
+    fn sload_gadget_simple_storage_accesses() {
+        use crate::witness::StorageAccess;
+
+        // The sload_gadget_simple scenario
+        let key = Word::from(0x030201u64);
+        let value = Word::from(0x060504u64);
+        for ctx in test_ctxs(key, value) {
+            let accesses = CircuitTestBuilder::new_from_test_ctx(ctx).run_with_storage_accesses();
+            let expected = [false, true].map(|is_warm| StorageAccess {
+                tx_id: 1,
+                address: MOCK_ACCOUNTS[0],
+                key,
+                value,
+                committed_value: value,
+                is_warm,
+            });
+            assert_eq!(accesses, expected);
+        }
+    }
 }
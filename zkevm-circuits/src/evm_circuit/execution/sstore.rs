@@ -1,3 +1,5 @@
+#[cfg(feature = "debug-table")]
+use crate::witness::StorageAccess;
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
@@ -126,7 +128,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
         );
 
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(10.expr()),
+            rw_counter: cb.rw_counter_delta(),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(2.expr()),
             reversible_write_counter: Delta(3.expr()),
@@ -201,6 +203,19 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
         self.is_warm
             .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
 
+        #[cfg(feature = "debug-table")]
+        block.storage_accesses.record(
+            step.rwc.0,
+            StorageAccess {
+                tx_id: tx.id,
+                address: call.address,
+                key,
+                value,
+                committed_value: original_value,
+                is_warm,
+            },
+        );
+
         let (tx_refund, tx_refund_prev) = block.get_rws(step, 9).tx_refund_value_pair();
         self.tx_refund_prev
             .assign(region, offset, Value::known(F::from(tx_refund_prev)))?;
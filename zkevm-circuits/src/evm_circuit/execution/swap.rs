@@ -45,7 +45,7 @@ impl<F: Field> ExecutionGadget<F> for SwapGadget<F> {
 
         // State transition
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(4.expr()),
+            rw_counter: cb.rw_counter_delta(),
             program_counter: Delta(1.expr()),
             gas_left: Delta(-OpcodeId::SWAP1.constant_gas_cost().expr()),
             ..Default::default()
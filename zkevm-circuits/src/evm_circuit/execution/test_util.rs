@@ -0,0 +1,348 @@
+use std::marker::PhantomData;
+
+use itertools::Itertools;
+use strum::IntoEnumIterator;
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{EVM_LOOKUP_COLS, MAX_STEP_HEIGHT, N_PHASE2_COLUMNS, STEP_WIDTH},
+        step::Step,
+        table::{FixedTableTag, Table},
+        util::{
+            constraint_builder::EVMConstraintBuilder, rlc, CachedRegion, CellType, StoredExpression,
+        },
+        witness::{Block, Rw},
+    },
+    table::{BytecodeTable, LookupTable, RwTable},
+    util::{log2_ceil, Challenges, Expr},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::{MockProver, VerifyFailure},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, FirstPhase, Fixed, SecondPhase, Selector,
+        ThirdPhase,
+    },
+};
+
+const NUM_BLINDING_ROWS: usize = 64;
+
+#[derive(Clone, Debug)]
+pub(crate) struct ExecutionGadgetTesterConfig<F: Field, G> {
+    q_usable: Selector,
+    fixed_table: [Column<Fixed>; 4],
+    byte_table: [Column<Fixed>; 1],
+    rw_table: RwTable,
+    bytecode_table: BytecodeTable,
+    advices: [Column<Advice>; STEP_WIDTH],
+    step: Step<F>,
+    height: usize,
+    stored_expressions: Vec<StoredExpression<F>>,
+    gadget: G,
+}
+
+/// Circuit made of a single execution gadget, which is assigned one step of
+/// `block` together with the state of the step that follows it. Only the rw,
+/// bytecode, fixed and byte tables are loaded, lookups into the other tables
+/// are not checked. The rw table is loaded from `rws` instead of the block, so
+/// that a test can hand-craft rows which disagree with the step witness.
+#[derive(Clone, Debug)]
+pub(crate) struct ExecutionGadgetTester<F: Field, G> {
+    block: Block<F>,
+    tx_index: usize,
+    step_index: usize,
+    rws: Vec<Rw>,
+    _marker: PhantomData<G>,
+}
+
+impl<F: Field, G: ExecutionGadget<F>> ExecutionGadgetTester<F, G> {
+    /// Test the `n`th step of `block` that is in `G::EXECUTION_STATE`. The step
+    /// must be followed by another step of the same transaction.
+    pub(crate) fn new(block: Block<F>, n: usize) -> Self {
+        let (tx_index, step_index) = block
+            .txs
+            .iter()
+            .enumerate()
+            .flat_map(|(tx_index, tx)| {
+                tx.steps
+                    .iter()
+                    .positions(|step| step.execution_state() == G::EXECUTION_STATE)
+                    .map(move |step_index| (tx_index, step_index))
+            })
+            .nth(n)
+            .unwrap_or_else(|| panic!("no step {} in {:?}", n, G::EXECUTION_STATE));
+        let rws = block.rws.table_assignments();
+
+        Self {
+            block,
+            tx_index,
+            step_index,
+            rws,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Modify the rows loaded into the rw table, the step witness is still
+    /// assigned from the block.
+    pub(crate) fn rw_table_modifier(mut self, modifier: impl FnOnce(&mut Vec<Rw>)) -> Self {
+        modifier(&mut self.rws);
+        self
+    }
+
+    /// Run the circuit with the MockProver and verify it.
+    pub(crate) fn run(self) -> Result<(), Vec<VerifyFailure>> {
+        let fixed_table_rows = fixed_table_tags()
+            .iter()
+            .map(|tag| tag.build::<F>().count())
+            .sum::<usize>()
+            + 1;
+        let bytecode_rows = self
+            .block
+            .bytecodes
+            .values()
+            .map(|bytecode| bytecode.bytes.len() + 1)
+            .sum::<usize>()
+            + 1;
+        let rows = [fixed_table_rows, bytecode_rows, self.rws.len() + 1, 256]
+            .into_iter()
+            .max()
+            .unwrap();
+        let k = log2_ceil(rows + NUM_BLINDING_ROWS);
+
+        MockProver::<F>::run(k, &self, vec![]).unwrap().verify()
+    }
+}
+
+/// The fixed table without the bitwise tags, which are too large for a small
+/// circuit.
+fn fixed_table_tags() -> Vec<FixedTableTag> {
+    FixedTableTag::iter()
+        .filter(|tag| {
+            !matches!(
+                tag,
+                FixedTableTag::BitwiseAnd | FixedTableTag::BitwiseOr | FixedTableTag::BitwiseXor
+            )
+        })
+        .collect()
+}
+
+impl<F: Field, G: ExecutionGadget<F>> Circuit<F> for ExecutionGadgetTester<F, G> {
+    type Config = (ExecutionGadgetTesterConfig<F, G>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            block: Block::default(),
+            tx_index: 0,
+            step_index: 0,
+            rws: vec![],
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let challenges = Challenges::construct(meta);
+        let challenges_exprs = challenges.exprs(meta);
+
+        let q_usable = meta.complex_selector();
+        let fixed_table = [(); 4].map(|_| meta.fixed_column());
+        let byte_table = [(); 1].map(|_| meta.fixed_column());
+        let rw_table = RwTable::construct(meta);
+        let bytecode_table = BytecodeTable::construct(meta);
+
+        let advices = [(); STEP_WIDTH]
+            .iter()
+            .enumerate()
+            .map(|(n, _)| {
+                if n < EVM_LOOKUP_COLS {
+                    meta.advice_column_in(ThirdPhase)
+                } else if n < EVM_LOOKUP_COLS + N_PHASE2_COLUMNS {
+                    meta.advice_column_in(SecondPhase)
+                } else {
+                    meta.advice_column_in(FirstPhase)
+                }
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let step_curr = Step::new(meta, advices, 0, false);
+
+        // Configure the gadget with the max height first so we can find out the actual
+        // height, as the EVM circuit does
+        let height = {
+            let dummy_step_next = Step::new(meta, advices, MAX_STEP_HEIGHT, true);
+            let mut cb = EVMConstraintBuilder::new(
+                step_curr.clone(),
+                dummy_step_next,
+                &challenges_exprs,
+                G::EXECUTION_STATE,
+            );
+            G::configure(&mut cb);
+            let (_, _, height) = cb.build();
+            height
+        };
+
+        let step_next = Step::new(meta, advices, height, true);
+        let mut cb = EVMConstraintBuilder::new(
+            step_curr.clone(),
+            step_next,
+            &challenges_exprs,
+            G::EXECUTION_STATE,
+        );
+        let gadget = G::configure(&mut cb);
+        let (constraints, stored_expressions, _) = cb.build();
+
+        // The tested step is neither the first nor the last one
+        let step_constraints = constraints
+            .step
+            .into_iter()
+            .chain(constraints.not_step_last)
+            .collect::<Vec<_>>();
+        if !step_constraints.is_empty() {
+            meta.create_gate(G::NAME, |meta| {
+                let q_usable = meta.query_selector(q_usable);
+                step_constraints
+                    .into_iter()
+                    .map(move |(name, constraint)| (name, q_usable.clone() * constraint))
+            });
+        }
+
+        for column in step_curr.cell_manager.columns().iter() {
+            match column.cell_type {
+                CellType::Lookup(table) => {
+                    let name = format!("{:?}", table);
+                    let table: &dyn LookupTable<F> = match table {
+                        Table::Fixed => &fixed_table,
+                        Table::Rw => &rw_table,
+                        Table::Bytecode => &bytecode_table,
+                        _ => continue,
+                    };
+                    meta.lookup_any(Box::leak(name.into_boxed_str()), |meta| {
+                        let table_expressions = table.table_exprs(meta);
+                        vec![(
+                            column.expr(),
+                            rlc::expr(&table_expressions, challenges_exprs.lookup_input()),
+                        )]
+                    });
+                }
+                CellType::LookupByte => {
+                    meta.lookup_any("Byte lookup", |meta| {
+                        let byte_table_expression = byte_table.table_exprs(meta)[0].clone();
+                        vec![(column.expr(), byte_table_expression)]
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        (
+            ExecutionGadgetTesterConfig {
+                q_usable,
+                fixed_table,
+                byte_table,
+                rw_table,
+                bytecode_table,
+                advices,
+                step: step_curr,
+                height,
+                stored_expressions,
+                gadget,
+            },
+            challenges,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let (config, challenges) = config;
+        let challenges = challenges.values(&mut layouter);
+
+        let block = &self.block;
+        let tx = &block.txs[self.tx_index];
+        let step = &tx.steps[self.step_index];
+        let step_next = &tx.steps[self.step_index + 1];
+
+        layouter.assign_region(
+            || "execution step",
+            |mut region| {
+                config.q_usable.enable(&mut region, 0)?;
+                let region = &mut CachedRegion::<'_, '_, F>::new(
+                    &mut region,
+                    &challenges,
+                    config.advices.to_vec(),
+                    MAX_STEP_HEIGHT * 3,
+                    0,
+                );
+
+                // Only the state of the next step is queried by the gadget
+                config.step.assign_exec_step(
+                    region,
+                    config.height,
+                    block,
+                    &tx.calls[step_next.call_index],
+                    step_next,
+                )?;
+
+                let call = &tx.calls[step.call_index];
+                config.step.assign_exec_step(region, 0, block, call, step)?;
+                config
+                    .gadget
+                    .assign_exec_step(region, 0, block, tx, call, step)?;
+                for stored_expression in config.stored_expressions.iter() {
+                    stored_expression.assign(region, 0)?;
+                }
+
+                Ok(())
+            },
+        )?;
+
+        config.rw_table.load(
+            &mut layouter,
+            &self.rws,
+            self.rws.len() + 1,
+            challenges.evm_word(),
+        )?;
+        config
+            .bytecode_table
+            .load(&mut layouter, block.bytecodes.values(), &challenges)?;
+
+        layouter.assign_region(
+            || "fixed table",
+            |mut region| {
+                for (offset, row) in std::iter::once([F::ZERO; 4])
+                    .chain(fixed_table_tags().iter().flat_map(|tag| tag.build()))
+                    .enumerate()
+                {
+                    for (column, value) in config.fixed_table.iter().zip_eq(row) {
+                        region.assign_fixed(|| "", *column, offset, || Value::known(value))?;
+                    }
+                }
+
+                Ok(())
+            },
+        )?;
+
+        layouter.assign_region(
+            || "byte table",
+            |mut region| {
+                for offset in 0..256 {
+                    region.assign_fixed(
+                        || "",
+                        config.byte_table[0],
+                        offset,
+                        || Value::known(F::from(offset as u64)),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
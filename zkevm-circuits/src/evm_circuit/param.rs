@@ -91,6 +91,11 @@ pub(crate) const N_BYTES_ACCOUNT_ADDRESS: usize = 20;
 pub(crate) const N_BYTES_MEMORY_ADDRESS: usize = 5;
 pub(crate) const N_BYTES_MEMORY_WORD_SIZE: usize = 4;
 
+// The largest memory address geth expands memory to, the memory word size at
+// this address still fits in N_BYTES_MEMORY_WORD_SIZE. Any larger address
+// halts with a gas uint overflow error.
+pub(crate) const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
+
 pub(crate) const STACK_CAPACITY: usize = 1024;
 
 // Number of bytes that will be used of prorgam counter. Although the maximum
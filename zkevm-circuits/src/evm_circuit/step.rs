@@ -1,4 +1,4 @@
-use super::util::{CachedRegion, CellManager, CellType};
+use super::util::{CachedRegion, CellConfig, CellManager, CellType};
 use crate::{
     evm_circuit::{
         param::{EXECUTION_STATE_HEIGHT_MAP, MAX_STEP_HEIGHT, STEP_STATE_HEIGHT, STEP_WIDTH},
@@ -78,6 +78,7 @@ pub enum ExecutionState {
     MSIZE,
     GAS,
     JUMPDEST,
+    PUSH0,
     PUSH, // PUSH1, PUSH2, ..., PUSH32
     DUP,  // DUP1, DUP2, ..., DUP16
     SWAP, // SWAP1, SWAP2, ..., SWAP16
@@ -226,6 +227,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::MSTORE => ExecutionState::MEMORY,
                     OpcodeId::MSTORE8 => ExecutionState::MEMORY,
                     OpcodeId::JUMPDEST => ExecutionState::JUMPDEST,
+                    OpcodeId::PUSH0 => ExecutionState::PUSH0,
                     OpcodeId::JUMP => ExecutionState::JUMP,
                     OpcodeId::JUMPI => ExecutionState::JUMPI,
                     OpcodeId::GASPRICE => ExecutionState::GASPRICE,
@@ -393,6 +395,7 @@ impl ExecutionState {
             Self::MSIZE => vec![OpcodeId::MSIZE],
             Self::GAS => vec![OpcodeId::GAS],
             Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
+            Self::PUSH0 => vec![OpcodeId::PUSH0],
             Self::PUSH => vec![
                 OpcodeId::PUSH1,
                 OpcodeId::PUSH2,
@@ -670,7 +673,13 @@ impl<F: Field> Step<F> {
         } else {
             MAX_STEP_HEIGHT // Query the entire current step.
         };
-        let mut cell_manager = CellManager::new(meta, height, &advices, offset);
+        let mut cell_manager = CellManager::new(
+            meta,
+            height,
+            &advices,
+            offset,
+            &CellConfig::evm_layout(height),
+        );
         let state = {
             StepState {
                 execution_state: DynamicSelectorHalf::new(
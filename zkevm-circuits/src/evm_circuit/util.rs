@@ -18,6 +18,7 @@ use halo2_proofs::{
 use itertools::Itertools;
 use std::{
     collections::BTreeMap,
+    fmt,
     hash::{Hash, Hasher},
 };
 
@@ -314,11 +315,41 @@ impl CellType {
     }
 }
 
+/// Layout of a group of consecutive columns in the `CellManager`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CellConfig {
+    pub(crate) cell_type: CellType,
+    pub(crate) columns: usize,
+    /// Number of rows that can be queried in each of the columns
+    pub(crate) height: usize,
+}
+
+impl CellConfig {
+    /// Column layout of an EVM step, with every column as tall as the step.
+    pub(crate) fn evm_layout(height: usize) -> Vec<Self> {
+        LOOKUP_CONFIG
+            .iter()
+            .map(|&(table, columns)| (CellType::Lookup(table), columns))
+            .chain([
+                (CellType::StoragePhase2, N_PHASE2_COLUMNS),
+                (CellType::StoragePermutation, N_COPY_COLUMNS),
+                (CellType::LookupByte, N_BYTE_LOOKUPS),
+            ])
+            .map(|(cell_type, columns)| Self {
+                cell_type,
+                columns,
+                height,
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct CellColumn<F> {
     pub(crate) index: usize,
     pub(crate) cell_type: CellType,
     pub(crate) height: usize,
+    pub(crate) height_limit: usize,
     pub(crate) expr: Expression<F>,
 }
 
@@ -328,6 +359,32 @@ impl<F: Field> Expr<F> for CellColumn<F> {
     }
 }
 
+/// Error returned when a `CellManager` has no cell left for a query.
+#[derive(Clone, Debug)]
+pub(crate) struct CellManagerError {
+    pub(crate) cell_type: CellType,
+    /// Largest height limit among the columns of `cell_type`
+    pub(crate) height_limit: usize,
+    /// Stats of the `CellManager` when the query failed, see
+    /// `CellManager::get_stats`
+    pub(crate) stats: BTreeMap<CellType, (usize, usize, usize)>,
+}
+
+impl fmt::Display for CellManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "not enough cells for query: {:?} (height limit {})",
+            self.cell_type, self.height_limit
+        )?;
+        writeln!(f, "cells allocated by type (columns, height, cells):")?;
+        for (cell_type, (columns, height, cells)) in self.stats.iter() {
+            writeln!(f, "  {:?}: ({}, {}, {})", cell_type, columns, height, cells)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct CellManager<F> {
     width: usize,
@@ -337,11 +394,15 @@ pub(crate) struct CellManager<F> {
 }
 
 impl<F: Field> CellManager<F> {
+    /// Lays out the `advices` following `cell_configs` in order. The columns
+    /// left over are used for `CellType::StoragePhase1` with the full
+    /// `height`.
     pub(crate) fn new(
         meta: &mut ConstraintSystem<F>,
         height: usize,
         advices: &[Column<Advice>],
         height_offset: usize,
+        cell_configs: &[CellConfig],
     ) -> Self {
         // Setup the columns and query the cells
         let width = advices.len();
@@ -356,41 +417,36 @@ impl<F: Field> CellManager<F> {
                     index: c,
                     cell_type: CellType::StoragePhase1,
                     height: 0,
+                    height_limit: height,
                     expr: cells[c * height].expr(),
                 });
             }
         });
 
         let mut column_idx = 0;
-
-        // Mark columns used for lookups in Phase3
-        for &(table, count) in LOOKUP_CONFIG {
-            for _ in 0usize..count {
-                columns[column_idx].cell_type = CellType::Lookup(table);
+        for config in cell_configs {
+            assert!(
+                config.height <= height,
+                "cell height {} of {:?} exceeds the height {}",
+                config.height,
+                config.cell_type,
+                height
+            );
+            for _ in 0..config.columns {
+                match config.cell_type {
+                    // Mark columns used for copy constraints
+                    CellType::StoragePermutation => meta.enable_equality(advices[column_idx]),
+                    CellType::LookupByte => {
+                        assert_eq!(advices[column_idx].column_type().phase(), 0)
+                    }
+                    _ => {}
+                }
+                columns[column_idx].cell_type = config.cell_type;
+                columns[column_idx].height_limit = config.height;
                 column_idx += 1;
             }
         }
 
-        // Mark columns used for Phase2 constraints
-        for _ in 0..N_PHASE2_COLUMNS {
-            columns[column_idx].cell_type = CellType::StoragePhase2;
-            column_idx += 1;
-        }
-
-        // Mark columns used for copy constraints
-        for _ in 0..N_COPY_COLUMNS {
-            meta.enable_equality(advices[column_idx]);
-            columns[column_idx].cell_type = CellType::StoragePermutation;
-            column_idx += 1;
-        }
-
-        // Mark columns used for byte lookup
-        for _ in 0..N_BYTE_LOOKUPS {
-            columns[column_idx].cell_type = CellType::LookupByte;
-            assert_eq!(advices[column_idx].column_type().phase(), 0);
-            column_idx += 1;
-        }
-
         Self {
             width,
             height,
@@ -400,25 +456,39 @@ impl<F: Field> CellManager<F> {
     }
 
     pub(crate) fn query_cells(&mut self, cell_type: CellType, count: usize) -> Vec<Cell<F>> {
+        self.try_query_cells(cell_type, count)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `query_cells`, but returns an error with the current
+    /// allocation stats when the cells of `cell_type` are exhausted.
+    pub(crate) fn try_query_cells(
+        &mut self,
+        cell_type: CellType,
+        count: usize,
+    ) -> Result<Vec<Cell<F>>, CellManagerError> {
         let mut cells = Vec::with_capacity(count);
         while cells.len() < count {
-            let column_idx = self.next_column(cell_type);
+            let column_idx = self.next_column(cell_type)?;
             let column = &mut self.columns[column_idx];
             cells.push(self.cells[column_idx * self.height + column.height].clone());
             column.height += 1;
         }
-        cells
+        Ok(cells)
     }
 
     pub(crate) fn query_cell(&mut self, cell_type: CellType) -> Cell<F> {
         self.query_cells(cell_type, 1)[0].clone()
     }
 
-    fn next_column(&self, cell_type: CellType) -> usize {
+    fn next_column(&self, cell_type: CellType) -> Result<usize, CellManagerError> {
         let mut best_index: Option<usize> = None;
         let mut best_height = self.height;
         for column in self.columns.iter() {
-            if column.cell_type == cell_type && column.height < best_height {
+            if column.cell_type == cell_type
+                && column.height < column.height_limit
+                && column.height < best_height
+            {
                 best_index = Some(column.index);
                 best_height = column.height;
             }
@@ -427,18 +497,28 @@ impl<F: Field> CellManager<F> {
         // better height
         if cell_type == CellType::StoragePhase1 {
             for column in self.columns.iter() {
-                if column.cell_type == CellType::StoragePermutation && column.height < best_height {
+                if column.cell_type == CellType::StoragePermutation
+                    && column.height < column.height_limit
+                    && column.height < best_height
+                {
                     best_index = Some(column.index);
                     best_height = column.height;
                 }
             }
         }
-        match best_index {
-            Some(index) => index,
-            // If we reach this case, it means that all the columns of cell_type have assignments
-            // taking self.height rows, so there's no more space.
-            None => panic!("not enough cells for query: {:?}", cell_type),
-        }
+        // If there's no index, it means that all the columns of cell_type have assignments
+        // taking their height_limit rows, so there's no more space.
+        best_index.ok_or_else(|| CellManagerError {
+            cell_type,
+            height_limit: self
+                .columns
+                .iter()
+                .filter(|column| column.cell_type == cell_type)
+                .map(|column| column.height_limit)
+                .max()
+                .unwrap_or(0),
+            stats: self.get_stats(),
+        })
     }
 
     pub(crate) fn get_height(&self) -> usize {
@@ -466,8 +546,32 @@ impl<F: Field> CellManager<F> {
     pub(crate) fn columns(&self) -> &[CellColumn<F>] {
         &self.columns
     }
+
+    /// Save the current height and height limit of every column.
+    pub(crate) fn snapshot(&self) -> CellSnapshot {
+        CellSnapshot(
+            self.columns
+                .iter()
+                .map(|column| (column.height, column.height_limit))
+                .collect(),
+        )
+    }
+
+    /// Roll the columns back to a `snapshot` taken from this `CellManager`.
+    /// Cells queried after the snapshot will be handed out again.
+    pub(crate) fn restore(&mut self, snapshot: &CellSnapshot) {
+        assert_eq!(snapshot.0.len(), self.columns.len());
+        for (column, &(height, height_limit)) in self.columns.iter_mut().zip(snapshot.0.iter()) {
+            column.height = height;
+            column.height_limit = height_limit;
+        }
+    }
 }
 
+/// Column heights of a `CellManager`, see `CellManager::snapshot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CellSnapshot(Vec<(usize, usize)>);
+
 #[derive(Clone, Debug)]
 pub(crate) struct RandomLinearCombination<F, const N: usize> {
     // random linear combination expression of cells
@@ -502,6 +606,23 @@ impl<F: Field, const N: usize> RandomLinearCombination<F, N> {
                 .collect()
         })
     }
+
+    /// Assign from big-endian bytes, the cells stay in little-endian.
+    pub(crate) fn assign_be(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        bytes: Option<[u8; N]>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.assign(
+            region,
+            offset,
+            bytes.map(|mut bytes| {
+                bytes.reverse();
+                bytes
+            }),
+        )
+    }
 }
 
 impl<F: Field, const N: usize> Expr<F> for RandomLinearCombination<F, N> {
@@ -662,3 +783,137 @@ impl<'a> StepRws<'a> {
         rw
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        math_gadget::test_util::*,
+        CachedRegion, CellConfig, CellManager, CellType, Word,
+    };
+    use crate::{evm_circuit::table::Table, util::Expr};
+    use eth_types::{Field, ToBigEndian, ToLittleEndian};
+    use halo2_proofs::{
+        halo2curves::bn256::Fr,
+        plonk::{ConstraintSystem, Error},
+    };
+
+    fn cell_manager(meta: &mut ConstraintSystem<Fr>) -> CellManager<Fr> {
+        let advices = [(); 4].map(|_| meta.advice_column());
+        let cell_configs = [
+            CellConfig {
+                cell_type: CellType::Lookup(Table::Fixed),
+                columns: 1,
+                height: 4,
+            },
+            CellConfig {
+                cell_type: CellType::LookupByte,
+                columns: 1,
+                height: 2,
+            },
+        ];
+        CellManager::new(meta, 4, &advices, 0, &cell_configs)
+    }
+
+    #[test]
+    fn cell_manager_per_type_height() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let mut cm = cell_manager(&mut meta);
+
+        cm.query_cells(CellType::Lookup(Table::Fixed), 3);
+        cm.query_cells(CellType::LookupByte, 2);
+        cm.query_cells(CellType::StoragePhase1, 6);
+
+        let stats = cm.get_stats();
+        assert_eq!(stats[&CellType::Lookup(Table::Fixed)], (1, 3, 3));
+        assert_eq!(stats[&CellType::LookupByte], (1, 2, 2));
+        assert_eq!(stats[&CellType::StoragePhase1], (2, 3, 6));
+        assert_eq!(cm.get_height(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "not enough cells for query: LookupByte")]
+    fn cell_manager_height_limit_exceeded() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let mut cm = cell_manager(&mut meta);
+
+        cm.query_cells(CellType::LookupByte, 3);
+    }
+
+    #[test]
+    fn cell_manager_error_stats() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let mut cm = cell_manager(&mut meta);
+
+        cm.query_cells(CellType::LookupByte, 2);
+        cm.query_cells(CellType::StoragePhase1, 1);
+        let err = cm.try_query_cells(CellType::LookupByte, 1).unwrap_err();
+        assert_eq!(err.cell_type, CellType::LookupByte);
+        assert_eq!(err.height_limit, 2);
+        assert_eq!(err.stats[&CellType::LookupByte], (1, 2, 2));
+        assert_eq!(err.stats[&CellType::StoragePhase1], (2, 1, 1));
+    }
+
+    #[test]
+    fn cell_manager_snapshot_restore() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let mut cm = cell_manager(&mut meta);
+
+        cm.query_cells(CellType::LookupByte, 1);
+        cm.query_cells(CellType::StoragePhase1, 3);
+        let snapshot = cm.snapshot();
+        let stats = cm.get_stats();
+        let next_cell = cm.query_cell(CellType::StoragePhase1);
+
+        cm.query_cells(CellType::LookupByte, 1);
+        cm.query_cells(CellType::Lookup(Table::Fixed), 4);
+        cm.restore(&snapshot);
+
+        assert_eq!(cm.snapshot(), snapshot);
+        assert_eq!(cm.get_stats(), stats);
+        let cell = cm.query_cell(CellType::StoragePhase1);
+        assert_eq!(
+            (cell.cell_column_index, cell.rotation),
+            (next_cell.cell_column_index, next_cell.rotation)
+        );
+    }
+
+    #[derive(Clone)]
+    /// WordAssignBeTestContainer: assign(le_bytes) == assign_be(be_bytes)
+    struct WordAssignBeTestContainer<F> {
+        le: Word<F>,
+        be: Word<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for WordAssignBeTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let le = cb.query_word_rlc();
+            let be = cb.query_word_rlc();
+            cb.require_equal("assign_be matches assign", le.expr(), be.expr());
+            WordAssignBeTestContainer { le, be }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[eth_types::Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            self.le
+                .assign(region, offset, Some(witnesses[0].to_le_bytes()))?;
+            self.be
+                .assign_be(region, offset, Some(witnesses[0].to_be_bytes()))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn word_assign_be() {
+        try_test!(
+            WordAssignBeTestContainer<Fr>,
+            vec![eth_types::Word::from(0x0102030405060708u64) << 128],
+            true,
+        );
+        try_test!(WordAssignBeTestContainer<Fr>, vec![WORD_SIGNED_MAX], true);
+    }
+}
@@ -272,6 +272,11 @@ pub(crate) struct EVMConstraintBuilder<'a, F> {
     execution_state: ExecutionState,
     constraints: Constraints<F>,
     rw_counter_offset: Expression<F>,
+    /// Number of rw lookups done unconditionally so far
+    min_rw_counter_offset: usize,
+    /// Number of rw lookups done so far, `None` once a lookup of unknown
+    /// width (like a copy lookup) is added
+    max_rw_counter_offset: Option<usize>,
     program_counter_offset: usize,
     stack_pointer_offset: Expression<F>,
     log_id_offset: usize,
@@ -314,6 +319,8 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
                 not_step_last: Vec::new(),
             },
             rw_counter_offset: 0.expr(),
+            min_rw_counter_offset: 0,
+            max_rw_counter_offset: Some(0),
             program_counter_offset: 0,
             stack_pointer_offset: 0.expr(),
             log_id_offset: 0,
@@ -367,6 +374,12 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.rw_counter_offset.clone()
     }
 
+    /// `rw_counter` transition of a step that does exactly the rw lookups
+    /// added so far.
+    pub(crate) fn rw_counter_delta(&self) -> Transition<Expression<F>> {
+        Transition::Delta(self.rw_counter_offset())
+    }
+
     pub(crate) fn program_counter_offset(&self) -> usize {
         self.program_counter_offset
     }
@@ -442,7 +455,8 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             &mut self.curr
         }
         .cell_manager
-        .query_cells(cell_type, count)
+        .try_query_cells(cell_type, count)
+        .unwrap_or_else(|err| panic!("{:?}: {}", self.execution_state, err))
     }
 
     pub(crate) fn word_rlc<const N: usize>(&self, bytes: [Expression<F>; N]) -> Expression<F> {
@@ -474,6 +488,10 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         &mut self,
         step_state_transition: StepStateTransition<F>,
     ) {
+        if let Transition::Delta(delta) = &step_state_transition.rw_counter {
+            self.validate_rw_counter_delta(delta);
+        }
+
         macro_rules! constrain {
             ($name:tt) => {
                 match step_state_transition.$name {
@@ -711,8 +729,10 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         // Manually constant folding is used here, since halo2 cannot do this
         // automatically. Better error message will be printed during circuit
         // debugging.
+        self.max_rw_counter_offset = self.max_rw_counter_offset.map(|max| max + 1);
         self.rw_counter_offset = match self.condition_expr_opt() {
             None => {
+                self.min_rw_counter_offset += 1;
                 if let Constant(v) = self.rw_counter_offset {
                     Constant(v + F::from(1u64))
                 } else {
@@ -1263,6 +1283,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             },
         );
         self.rw_counter_offset = self.rw_counter_offset.clone() + self.condition_expr() * rwc_inc;
+        self.max_rw_counter_offset = None;
     }
 
     // Exponentiation Table
@@ -1321,6 +1342,43 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    /// Check that a constant `rw_counter` delta matches the number of rw
+    /// lookups done so far. Both are only known at configuration time when
+    /// the lookups are unconditional, otherwise the check is skipped.
+    fn validate_rw_counter_delta(&self, delta: &Expression<F>) {
+        if let (Constant(delta), Constant(offset)) = (delta, &self.rw_counter_offset) {
+            assert_eq!(
+                delta.get_lower_128(),
+                offset.get_lower_128(),
+                "{:?}: declared rw_counter delta doesn't match the number of rw lookups",
+                self.execution_state,
+            );
+        }
+    }
+
+    /// Constrain the number of rw lookups added so far to one of `deltas`,
+    /// for gadgets with conditional rw lookups whose `rw_counter` delta
+    /// can't be checked at configuration time. Panics if any of `deltas`
+    /// can't be reached by those lookups.
+    pub(crate) fn allowed_rw_counter_deltas(&mut self, deltas: &[usize]) {
+        for &delta in deltas {
+            assert!(
+                delta >= self.min_rw_counter_offset
+                    && self.max_rw_counter_offset.map_or(true, |max| delta <= max),
+                "{:?}: allowed rw_counter delta {} is out of the {}..={:?} rw lookups",
+                self.execution_state,
+                delta,
+                self.min_rw_counter_offset,
+                self.max_rw_counter_offset,
+            );
+        }
+        self.require_in_set(
+            "rw_counter delta is one of the allowed values",
+            self.rw_counter_offset(),
+            deltas.iter().map(|delta| delta.expr()).collect(),
+        );
+    }
+
     // General
 
     pub(crate) fn condition<R>(
@@ -1521,3 +1579,107 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{EVMConstraintBuilder, StepStateTransition, Transition::Delta};
+    use crate::{
+        evm_circuit::{
+            param::{LOOKUP_CONFIG, MAX_STEP_HEIGHT, N_PHASE2_COLUMNS, STEP_WIDTH},
+            step::{ExecutionState, Step},
+        },
+        util::{Challenges, Expr},
+    };
+    use halo2_proofs::{
+        halo2curves::bn256::Fr,
+        plonk::{ConstraintSystem, FirstPhase, SecondPhase, ThirdPhase},
+    };
+
+    /// Configure a step with `configure` using a fresh constraint builder.
+    fn configure_step(configure: impl FnOnce(&mut EVMConstraintBuilder<Fr>)) {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let challenges = Challenges::construct(&mut meta);
+        let challenges_exprs = challenges.exprs(&mut meta);
+
+        let lookup_column_count: usize = LOOKUP_CONFIG.iter().map(|(_, count)| *count).sum();
+        let advices = [(); STEP_WIDTH]
+            .iter()
+            .enumerate()
+            .map(|(n, _)| {
+                if n < lookup_column_count {
+                    meta.advice_column_in(ThirdPhase)
+                } else if n < lookup_column_count + N_PHASE2_COLUMNS {
+                    meta.advice_column_in(SecondPhase)
+                } else {
+                    meta.advice_column_in(FirstPhase)
+                }
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let step_curr = Step::new(&mut meta, advices, 0, false);
+        let step_next = Step::new(&mut meta, advices, MAX_STEP_HEIGHT, true);
+        let mut cb =
+            EVMConstraintBuilder::new(step_curr, step_next, &challenges_exprs, ExecutionState::POP);
+        configure(&mut cb);
+    }
+
+    /// Configure a step that pops `pops` stack values and declares
+    /// `rw_counter_delta` in its state transition.
+    fn configure_stack_pops(pops: usize, rw_counter_delta: usize) {
+        configure_step(|cb| {
+            for _ in 0..pops {
+                let value = cb.query_cell_phase2();
+                cb.stack_pop(value.expr());
+            }
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(rw_counter_delta.expr()),
+                ..Default::default()
+            });
+        });
+    }
+
+    /// Configure a step that pops one stack value and `pops` more only when
+    /// a flag is set, and declares `allowed` as its rw_counter deltas.
+    fn configure_conditional_stack_pops(pops: usize, allowed: &[usize]) {
+        configure_step(|cb| {
+            let value = cb.query_cell_phase2();
+            cb.stack_pop(value.expr());
+            let flag = cb.query_bool();
+            cb.condition(flag.expr(), |cb| {
+                for _ in 0..pops {
+                    let value = cb.query_cell_phase2();
+                    cb.stack_pop(value.expr());
+                }
+            });
+            cb.allowed_rw_counter_deltas(allowed);
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: cb.rw_counter_delta(),
+                ..Default::default()
+            });
+        });
+    }
+
+    #[test]
+    fn rw_counter_delta_matches_lookups() {
+        configure_stack_pops(2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "declared rw_counter delta doesn't match the number of rw lookups")]
+    fn rw_counter_delta_mismatch() {
+        configure_stack_pops(2, 1);
+    }
+
+    #[test]
+    fn rw_counter_delta_allowed_with_conditional_lookups() {
+        configure_conditional_stack_pops(2, &[1, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "allowed rw_counter delta 4 is out of the 1..=Some(3) rw lookups")]
+    fn rw_counter_delta_not_allowed_with_conditional_lookups() {
+        configure_conditional_stack_pops(2, &[1, 4]);
+    }
+}
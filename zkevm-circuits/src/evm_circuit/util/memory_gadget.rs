@@ -1,12 +1,17 @@
 use super::{constraint_builder::ConstrainBuilderCommon, CachedRegion};
 use crate::{
     evm_circuit::{
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
+        param::{
+            MAX_EXPANDED_MEMORY_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS,
+            N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+        },
         util::{
             constraint_builder::EVMConstraintBuilder,
             from_bytes,
-            math_gadget::{ConstantDivisionGadget, IsZeroGadget, MinMaxGadget, RangeCheckGadget},
-            select, sum, Cell, CellType, MemoryAddress,
+            math_gadget::{
+                ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget, RangeCheckGadget,
+            },
+            select, sum, Cell, CellType, MemoryAddress, Word,
         },
     },
     util::Expr,
@@ -160,6 +165,119 @@ impl<F: Field> MemoryAddressGadget<F> {
     }
 }
 
+/// Checks whether the memory range given by a full word offset and length
+/// can be expanded to, which geth checks before charging any memory gas. An
+/// empty range never overflows. Otherwise both words have to fit in a u64 and
+/// their sum must not exceed `MAX_EXPANDED_MEMORY_ADDRESS`, or geth halts with
+/// a gas uint overflow error.
+#[derive(Clone, Debug)]
+pub(crate) struct U64OverflowGadget<F> {
+    memory_offset: Word<F>,
+    memory_length: Word<F>,
+    memory_length_is_zero: IsZeroGadget<F>,
+    high_bytes_are_zero: IsZeroGadget<F>,
+    end_offset_in_range: LtGadget<F, { N_BYTES_U64 + 1 }>,
+}
+
+impl<F: Field> U64OverflowGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        memory_offset: Word<F>,
+        memory_length: Word<F>,
+    ) -> Self {
+        let memory_length_is_zero = IsZeroGadget::construct(cb, sum::expr(&memory_length.cells));
+        let high_bytes_are_zero = IsZeroGadget::construct(
+            cb,
+            sum::expr(&memory_offset.cells[N_BYTES_U64..])
+                + sum::expr(&memory_length.cells[N_BYTES_U64..]),
+        );
+        // The sum of the low u64 parts is at most 2^65, which fits in 9 bytes
+        let end_offset_in_range = LtGadget::construct(
+            cb,
+            from_bytes::expr(&memory_offset.cells[..N_BYTES_U64])
+                + from_bytes::expr(&memory_length.cells[..N_BYTES_U64]),
+            (MAX_EXPANDED_MEMORY_ADDRESS + 1).expr(),
+        );
+
+        Self {
+            memory_offset,
+            memory_length,
+            memory_length_is_zero,
+            high_bytes_are_zero,
+            end_offset_in_range,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        memory_offset: U256,
+        memory_length: U256,
+    ) -> Result<(bool, u64), Error> {
+        let memory_offset_bytes = memory_offset.to_le_bytes();
+        let memory_length_bytes = memory_length.to_le_bytes();
+        self.memory_offset
+            .assign(region, offset, Some(memory_offset_bytes))?;
+        self.memory_length
+            .assign(region, offset, Some(memory_length_bytes))?;
+        self.memory_length_is_zero
+            .assign(region, offset, sum::value(&memory_length_bytes))?;
+        self.high_bytes_are_zero.assign(
+            region,
+            offset,
+            sum::value::<F>(&memory_offset_bytes[N_BYTES_U64..])
+                + sum::value::<F>(&memory_length_bytes[N_BYTES_U64..]),
+        )?;
+        let end_offset = memory_offset.low_u64() as u128 + memory_length.low_u64() as u128;
+        self.end_offset_in_range.assign(
+            region,
+            offset,
+            F::from_u128(end_offset),
+            F::from(MAX_EXPANDED_MEMORY_ADDRESS + 1),
+        )?;
+
+        if memory_length.is_zero() {
+            return Ok((true, 0));
+        }
+        let within_range = memory_offset.bits() <= 64
+            && memory_length.bits() <= 64
+            && end_offset <= MAX_EXPANDED_MEMORY_ADDRESS as u128;
+        Ok((
+            within_range,
+            if within_range { end_offset as u64 } else { 0 },
+        ))
+    }
+
+    /// `1` when the non-empty range fits in expandable memory.
+    fn has_length_in_range(&self) -> Expression<F> {
+        (1.expr() - self.memory_length_is_zero.expr())
+            * self.high_bytes_are_zero.expr()
+            * self.end_offset_in_range.expr()
+    }
+
+    /// `1` when the range can be expanded to, `0` when it overflows.
+    pub(crate) fn within_range(&self) -> Expression<F> {
+        self.memory_length_is_zero.expr() + self.has_length_in_range()
+    }
+
+    pub(crate) fn overflow(&self) -> Expression<F> {
+        1.expr() - self.within_range()
+    }
+
+    /// The length of the range, or `0` when it overflows.
+    pub(crate) fn length(&self) -> Expression<F> {
+        self.has_length_in_range() * from_bytes::expr(&self.memory_length.cells[..N_BYTES_U64])
+    }
+
+    /// The end offset of the range, or `0` when it is empty or overflows.
+    pub(crate) fn end_offset(&self) -> Expression<F> {
+        self.has_length_in_range()
+            * (from_bytes::expr(&self.memory_offset.cells[..N_BYTES_U64])
+                + from_bytes::expr(&self.memory_length.cells[..N_BYTES_U64]))
+    }
+}
+
 /// Calculates the memory size in words required for a memory access at the
 /// specified address.
 /// `memory_word_size = ceil(address/32) = floor((address + 31) / 32)`
@@ -1,14 +1,17 @@
 #![allow(unused_imports)]
 use super::*;
-use crate::util::unusable_rows;
-use eth_types::Field;
+use crate::{util::unusable_rows, witness::block_convert};
+use bus_mapping::mock::BlockData;
+use eth_types::{geth_types::GethData, Bytecode, Field};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
     dev::MockProver,
     halo2curves::bn256::Fr,
     plonk::{Circuit, ConstraintSystem, Error},
 };
+use itertools::Itertools;
 use log::error;
+use mock::TestContext;
 use std::iter::zip;
 
 use super::util::{target_part_sizes, target_part_sizes_rot, WordParts};
@@ -143,3 +146,43 @@ fn test_word_parts() {
         }
     }
 }
+
+#[test]
+fn keccak_inputs_deduplicated() {
+    // Hash the same 1 KiB of memory 10 times
+    let mut code = Bytecode::default();
+    for _ in 0..10 {
+        code.op_sha3(0x00, 0x400).op_pop();
+    }
+    code.op_stop();
+    let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+        .unwrap()
+        .into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    let buffer = vec![0u8; 0x400];
+    assert_eq!(builder.block.sha3_inputs, vec![buffer.clone(); 10]);
+    assert_eq!(
+        block
+            .keccak_inputs
+            .iter()
+            .filter(|input| **input == buffer)
+            .count(),
+        1
+    );
+    assert!(block.keccak_inputs.iter().all_unique());
+
+    // Without deduplication the other 9 copies would be hashed as well, each
+    // one taking 8 chunks of 136 bytes.
+    let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
+    let rows_deduplicated = KeccakCircuit::<Fr>::min_num_rows_block(&block).0;
+    block
+        .keccak_inputs
+        .extend(builder.block.sha3_inputs.iter().skip(1).cloned());
+    let rows_duplicated = KeccakCircuit::<Fr>::min_num_rows_block(&block).0;
+    assert_eq!(rows_duplicated - rows_deduplicated, 9 * 8 * rows_per_chunk);
+}
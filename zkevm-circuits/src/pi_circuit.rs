@@ -1154,7 +1154,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 coinbase: block.context.coinbase,
                 timestamp: block.context.timestamp,
                 number: block.context.number.as_u64().into(),
-                difficulty: block.context.difficulty,
+                difficulty: block.context.difficulty_or_prevrandao(),
+                mix_hash: None,
                 gas_limit: block.context.gas_limit.into(),
                 base_fee: block.context.base_fee,
             },
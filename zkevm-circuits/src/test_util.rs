@@ -1,5 +1,7 @@
 //! Testing utilities
 
+#[cfg(feature = "debug-table")]
+use crate::witness::{StorageAccess, StorageAccessRecorder};
 use crate::{
     evm_circuit::{cached::EvmCircuitCached, EvmCircuit},
     state_circuit::StateCircuit,
@@ -81,6 +83,8 @@ pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
     evm_checks: Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>,
     state_checks: Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>,
     block_modifiers: Vec<Box<dyn Fn(&mut Block<Fr>)>>,
+    #[cfg(feature = "debug-table")]
+    storage_accesses: StorageAccessRecorder,
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
@@ -103,6 +107,8 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                 )
             }),
             block_modifiers: vec![],
+            #[cfg(feature = "debug-table")]
+            storage_accesses: StorageAccessRecorder::default(),
         }
     }
 
@@ -208,6 +214,13 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             panic!("No attribute to build a block was passed to the CircuitTestBuilder")
         };
 
+        // Record the storage accesses of the EVM circuit run below
+        #[cfg(feature = "debug-table")]
+        let block = Block {
+            storage_accesses: self.storage_accesses,
+            ..block
+        };
+
         // Run evm circuit test
         {
             let k = block.get_test_degree();
@@ -240,4 +253,14 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             self.state_checks.as_ref()(prover, &rows, &rows);
         }
     }
+
+    #[cfg(feature = "debug-table")]
+    /// Runs the circuits like [`Self::run`] and returns the storage accesses
+    /// that the EVM circuit recorded while assigning the SLOAD and SSTORE
+    /// steps.
+    pub fn run_with_storage_accesses(self) -> Vec<StorageAccess> {
+        let storage_accesses = self.storage_accesses.clone();
+        self.run();
+        storage_accesses.accesses()
+    }
 }
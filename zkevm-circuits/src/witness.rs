@@ -4,6 +4,8 @@
 
 mod block;
 pub use block::{block_convert, Block, BlockContext};
+#[cfg(feature = "debug-table")]
+pub use block::{StorageAccess, StorageAccessRecorder};
 mod bytecode;
 pub use bytecode::Bytecode;
 mod mpt;
@@ -1,4 +1,9 @@
 use std::collections::HashMap;
+#[cfg(feature = "debug-table")]
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     evm_circuit::{detect_fixed_table_tags, util::rlc, EvmCircuit},
@@ -51,6 +56,9 @@ pub struct Block<F> {
     pub keccak_inputs: Vec<Vec<u8>>,
     /// Original Block from geth
     pub eth_block: eth_types::Block<eth_types::Transaction>,
+    /// Storage accesses recorded by the EVM circuit
+    #[cfg(feature = "debug-table")]
+    pub storage_accesses: StorageAccessRecorder,
 }
 
 impl<F: Field> Block<F> {
@@ -142,6 +150,11 @@ pub struct BlockContext {
     pub timestamp: Word,
     /// The difficulty of the blcok
     pub difficulty: Word,
+    /// The prevrandao (mix hash) of the block
+    pub prevrandao: Word,
+    /// Whether the block is post-merge, in which case DIFFICULTY returns
+    /// `prevrandao` instead of `difficulty`
+    pub is_merge: bool,
     /// The base fee, the minimum amount of gas fee for a transaction
     pub base_fee: Word,
     /// The hash of previous blocks
@@ -151,6 +164,15 @@ pub struct BlockContext {
 }
 
 impl BlockContext {
+    /// Value returned by the DIFFICULTY (PREVRANDAO since the merge) opcode
+    pub fn difficulty_or_prevrandao(&self) -> Word {
+        if self.is_merge {
+            self.prevrandao
+        } else {
+            self.difficulty
+        }
+    }
+
     /// Assignments for block table
     pub fn table_assignments<F: Field>(&self, randomness: Value<F>) -> Vec<[Value<F>; 3]> {
         [
@@ -173,8 +195,9 @@ impl BlockContext {
                 [
                     Value::known(F::from(BlockContextFieldTag::Difficulty as u64)),
                     Value::known(F::ZERO),
-                    randomness
-                        .map(|randomness| rlc::value(&self.difficulty.to_le_bytes(), randomness)),
+                    randomness.map(|randomness| {
+                        rlc::value(&self.difficulty_or_prevrandao().to_le_bytes(), randomness)
+                    }),
                 ],
                 [
                     Value::known(F::from(BlockContextFieldTag::GasLimit as u64)),
@@ -222,6 +245,8 @@ impl From<&circuit_input_builder::Block> for BlockContext {
             number: block.number,
             timestamp: block.timestamp,
             difficulty: block.difficulty,
+            prevrandao: block.prevrandao,
+            is_merge: block.is_merge,
             base_fee: block.base_fee,
             history_hashes: block.history_hashes.clone(),
             chain_id: block.chain_id,
@@ -229,6 +254,47 @@ impl From<&circuit_input_builder::Block> for BlockContext {
     }
 }
 
+/// A storage access of an SLOAD or SSTORE step, with the values its gadget
+/// assigned to the EVM circuit cells.
+#[cfg(feature = "debug-table")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageAccess {
+    /// Transaction id
+    pub tx_id: usize,
+    /// Address of the accessed account
+    pub address: Address,
+    /// Storage key
+    pub key: Word,
+    /// Value loaded by SLOAD or written by SSTORE
+    pub value: Word,
+    /// Value of the slot at the beginning of the transaction
+    pub committed_value: Word,
+    /// Whether the slot was already in the access list
+    pub is_warm: bool,
+}
+
+/// Storage accesses recorded by the SLOAD and SSTORE gadgets while the EVM
+/// circuit assigns their steps. Clones share the same record, so the
+/// accesses can be read back after a circuit built from a clone of the block
+/// is synthesized.
+#[cfg(feature = "debug-table")]
+#[derive(Debug, Clone, Default)]
+pub struct StorageAccessRecorder(Arc<Mutex<BTreeMap<usize, StorageAccess>>>);
+
+#[cfg(feature = "debug-table")]
+impl StorageAccessRecorder {
+    /// Record the access of the step starting at `rw_counter`. A step can be
+    /// assigned more than once, only its last assignment is kept.
+    pub(crate) fn record(&self, rw_counter: usize, access: StorageAccess) {
+        self.0.lock().unwrap().insert(rw_counter, access);
+    }
+
+    /// Recorded storage accesses in execution order
+    pub fn accesses(&self) -> Vec<StorageAccess> {
+        self.0.lock().unwrap().values().cloned().collect()
+    }
+}
+
 /// Convert a block struct in bus-mapping to a witness block used in circuits
 pub fn block_convert<F: Field>(
     block: &circuit_input_builder::Block,
@@ -265,5 +331,7 @@ pub fn block_convert<F: Field>(
         prev_state_root: block.prev_state_root,
         keccak_inputs: circuit_input_builder::keccak_inputs(block, code_db)?,
         eth_block: block.eth_block.clone(),
+        #[cfg(feature = "debug-table")]
+        storage_accesses: StorageAccessRecorder::default(),
     })
 }
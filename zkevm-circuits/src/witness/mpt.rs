@@ -53,6 +53,11 @@ impl MptUpdates {
         key(row).map(|key| *self.updates.get(&key).expect("missing key in mpt updates"))
     }
 
+    /// Squash the accesses to each key into a single update, from the
+    /// `value_prev` of its first access to the `value` of its last one.
+    /// Storage keys include the tx id, as in the state circuit, so a slot
+    /// accessed by several transactions gets one update per transaction,
+    /// each starting from that transaction's committed value.
     pub(crate) fn mock_from(rows: &[Rw]) -> Self {
         let mock_old_root = Word::from(0xcafeu64);
         let map: BTreeMap<_, _> = rows
@@ -244,3 +249,118 @@ fn value_prev(row: &Rw) -> Word {
         _ => unreachable!(),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    // Storage accesses to a single slot, given as (value_prev, value) in
+    // rw_counter order, all starting from `committed_value`.
+    fn storage_rows(committed_value: u64, writes: &[(u64, u64)]) -> Vec<Rw> {
+        tx_storage_rows(1, committed_value, writes)
+    }
+
+    // Same as `storage_rows`, within the transaction `tx_id`.
+    fn tx_storage_rows(tx_id: usize, committed_value: u64, writes: &[(u64, u64)]) -> Vec<Rw> {
+        writes
+            .iter()
+            .enumerate()
+            .map(|(i, &(value_prev, value))| Rw::AccountStorage {
+                rw_counter: 100 * tx_id + i + 1,
+                is_write: true,
+                account_address: Address::repeat_byte(0xff),
+                storage_key: Word::from(0x1234u64),
+                value: Word::from(value),
+                value_prev: Word::from(value_prev),
+                tx_id,
+                committed_value: Word::from(committed_value),
+            })
+            .collect()
+    }
+
+    fn squashed(rows: &[Rw]) -> (Fr, Word, Word) {
+        let updates = MptUpdates::mock_from(rows);
+        assert_eq!(updates.updates.len(), 1);
+        let update = updates.get(&rows[0]).unwrap();
+        (update.proof_type(), update.old_value, update.new_value)
+    }
+
+    fn proof_type(proof_type: MPTProofType) -> Fr {
+        Fr::from(proof_type as u64)
+    }
+
+    #[test]
+    fn squash_write_write() {
+        let rows = storage_rows(1, &[(1, 2), (2, 3)]);
+        assert_eq!(
+            squashed(&rows),
+            (
+                proof_type(MPTProofType::StorageMod),
+                1u64.into(),
+                3u64.into()
+            )
+        );
+    }
+
+    #[test]
+    fn squash_write_revert_write() {
+        // The revert of the first write is the write (2 -> 1)
+        let rows = storage_rows(1, &[(1, 2), (2, 1), (1, 4)]);
+        assert_eq!(
+            squashed(&rows),
+            (
+                proof_type(MPTProofType::StorageMod),
+                1u64.into(),
+                4u64.into()
+            )
+        );
+    }
+
+    #[test]
+    fn squash_write_back_to_original() {
+        let rows = storage_rows(5, &[(5, 6), (6, 5)]);
+        assert_eq!(
+            squashed(&rows),
+            (
+                proof_type(MPTProofType::StorageMod),
+                5u64.into(),
+                5u64.into()
+            )
+        );
+
+        let rows = storage_rows(0, &[(0, 6), (6, 0)]);
+        assert_eq!(
+            squashed(&rows),
+            (
+                proof_type(MPTProofType::NonExistingStorageProof),
+                Word::zero(),
+                Word::zero()
+            )
+        );
+    }
+
+    #[test]
+    fn squash_per_transaction() {
+        // Both transactions write to the same slot, the second one starts from
+        // the value left by the first.
+        let tx_1_rows = tx_storage_rows(1, 1, &[(1, 2), (2, 3)]);
+        let tx_2_rows = tx_storage_rows(2, 3, &[(3, 4), (4, 5)]);
+        let rows = [tx_1_rows.clone(), tx_2_rows.clone()].concat();
+
+        let updates = MptUpdates::mock_from(&rows);
+        assert_eq!(updates.updates.len(), 2);
+
+        let tx_1_update = updates.get(&tx_1_rows[0]).unwrap();
+        let tx_2_update = updates.get(&tx_2_rows[0]).unwrap();
+        assert_eq!(
+            (tx_1_update.old_value, tx_1_update.new_value),
+            (1u64.into(), 3u64.into())
+        );
+        assert_eq!(
+            (tx_2_update.old_value, tx_2_update.new_value),
+            (3u64.into(), 5u64.into())
+        );
+        assert_eq!(tx_1_update.new_root, tx_2_update.old_root);
+    }
+}